@@ -13,18 +13,18 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
-#[cfg(not(features_no_atomic_cas))]
+#[cfg(not(futures_no_atomic_cas))]
 #[cfg(feature = "alloc")]
 extern crate alloc;
 
-// #[cfg(not(features_no_atomic_cas))]
-// #[cfg(feature = "alloc")]
+#[cfg(not(futures_no_atomic_cas))]
+#[cfg(feature = "alloc")]
 mod lock;
 
-#[cfg(not(features_no_atomic_cas))]
+#[cfg(not(futures_no_atomic_cas))]
 #[cfg(feature = "std")]
 pub mod mpsc;
 
-// #[cfg(not(features_no_atomic_cas))]
-// #[cfg(feature = "alloc")]
+#[cfg(not(futures_no_atomic_cas))]
+#[cfg(feature = "alloc")]
 pub mod oneshot;
@@ -1,4 +1,5 @@
 use core::cell::UnsafeCell;
+use core::marker::PhantomData;
 use core::ops::{Deref, DerefMut};
 use core::sync::atomic::AtomicBool;
 use core::sync::atomic::Ordering::SeqCst;
@@ -11,10 +12,22 @@ pub(crate) struct Lock<T> {
 
 pub(crate) struct TryLock<'a, T> {
     __ptr: &'a Lock<T>,
+    // Opts out of the auto traits, which are implemented below with the
+    // bounds of a mutex guard instead of those of `&Lock<T>`.
+    _marker: PhantomData<*mut T>,
 }
 
+// SAFETY: the lock owns a `T`, so moving it moves a `T`.
 unsafe impl<T: Send> Send for Lock<T> {}
-unsafe impl<T: Sync> Sync for Lock<T> {}
+// SAFETY: like a mutex, the data is only reachable through a `TryLock`
+// guard, which one thread holds at a time, so `T: Sync` isn't needed.
+unsafe impl<T: Send> Sync for Lock<T> {}
+
+// SAFETY: the guard hands out `&mut T`, and unlocking is a plain atomic
+// store, so it can be dropped on any thread.
+unsafe impl<T: Send> Send for TryLock<'_, T> {}
+// SAFETY: a shared guard only hands out `&T`.
+unsafe impl<T: Sync> Sync for TryLock<'_, T> {}
 
 impl<T> Lock<T> {
     pub(crate) fn new(t: T) -> Self {
@@ -36,7 +49,10 @@ impl<T> Lock<T> {
     #[must_use]
     pub(crate) fn try_lock(&self) -> Option<TryLock<'_, T>> {
         if !self.locked.swap(true, SeqCst) {
-            Some(TryLock { __ptr: self })
+            Some(TryLock {
+                __ptr: self,
+                _marker: PhantomData,
+            })
         } else {
             None
         }
@@ -1,7 +1,6 @@
 //! A channel for sending a single message between asynchronous tasks.
 //!
 //! This is single-producer, single-consumer channel.
-extern crate alloc;
 use crate::lock::Lock;
 use alloc::sync::Arc;
 use core::fmt;
@@ -99,7 +98,7 @@ impl<T> Inner<T> {
     fn drop_tx(&self) {
         self.complete.store(true, SeqCst);
 
-        if let Some(mut slot) = self.tx_task.try_lock() {
+        if let Some(mut slot) = self.rx_task.try_lock() {
             if let Some(task) = slot.take() {
                 drop(slot);
                 task.wake();
@@ -112,7 +111,7 @@ impl<T> Inner<T> {
     }
 
     fn close_rx(&self) {
-        self.complete.store(false, SeqCst);
+        self.complete.store(true, SeqCst);
         if let Some(mut handle) = self.tx_task.try_lock() {
             if let Some(task) = handle.take() {
                 drop(handle);
@@ -122,7 +121,70 @@ impl<T> Inner<T> {
     }
 
     fn try_recv(&self) -> Result<Option<T>, Canceled> {
-        todo!()
+        // If we're complete, either `::close_rx` or `::drop_tx` was called.
+        // We can assume a successful send if data is present.
+        if self.complete.load(SeqCst) {
+            if let Some(mut slot) = self.data.try_lock() {
+                if let Some(data) = slot.take() {
+                    return Ok(Some(data));
+                }
+            }
+            Err(Canceled)
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn recv(&self, ctx: &mut Context<'_>) -> Poll<Result<T, Canceled>> {
+        // Check to see if some data has arrived. If it hasn't then we need to
+        // block our task.
+        //
+        // If we fail to acquire the `rx_task` lock then the sender is in
+        // `drop_tx` right now and we'll see the completion below.
+        let done = if self.complete.load(SeqCst) {
+            true
+        } else {
+            let task = ctx.waker().clone();
+            match self.rx_task.try_lock() {
+                Some(mut slot) => {
+                    *slot = Some(task);
+                    false
+                }
+                None => true,
+            }
+        };
+
+        // Re-check `complete` after storing our task, the sender may have
+        // finished in the meantime without seeing our waker.
+        if done || self.complete.load(SeqCst) {
+            if let Some(mut slot) = self.data.try_lock() {
+                if let Some(data) = slot.take() {
+                    return Poll::Ready(Ok(data));
+                }
+            }
+            Poll::Ready(Err(Canceled))
+        } else {
+            Poll::Pending
+        }
+    }
+
+    fn drop_rx(&self) {
+        self.complete.store(true, SeqCst);
+
+        // Release our own task handle, we won't be polled again.
+        if let Some(mut slot) = self.rx_task.try_lock() {
+            let task = slot.take();
+            drop(slot);
+            drop(task);
+        }
+
+        // Notify a sender blocked in `poll_canceled`.
+        if let Some(mut handle) = self.tx_task.try_lock() {
+            if let Some(task) = handle.take() {
+                drop(handle);
+                task.wake();
+            }
+        }
     }
 }
 
@@ -162,10 +224,21 @@ impl<T: fmt::Debug> fmt::Debug for Sender<T> {
     }
 }
 
+/// Error returned from a [`Receiver`] when the corresponding [`Sender`] is
+/// dropped.
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub struct Canceled;
 
+impl fmt::Display for Canceled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "oneshot canceled")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Canceled {}
 
+#[must_use = "futures do nothing unless you `.await` or poll them"]
 #[derive(Debug)]
 pub struct Cancellation<'a, T> {
     inner: &'a mut Sender<T>,
@@ -177,4 +250,162 @@ impl<T> Future for Cancellation<'_, T> {
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         self.inner.poll_canceled(cx)
     }
-}
\ No newline at end of file
+}
+
+impl<T> Receiver<T> {
+    /// Gracefully close this receiver, preventing any subsequent attempts to
+    /// send to it.
+    ///
+    /// A value already sent before the call to `close` can still be received
+    /// through `try_recv` or by polling the receiver.
+    pub fn close(&mut self) {
+        self.inner.close_rx()
+    }
+
+    /// Attempts to receive a message outside of the context of a task.
+    ///
+    /// Returns `Ok(None)` if the sender has not completed yet, `Ok(Some(t))`
+    /// if a value was sent, and `Err(Canceled)` if the sender was dropped
+    /// without sending a value.
+    pub fn try_recv(&mut self) -> Result<Option<T>, Canceled> {
+        self.inner.try_recv()
+    }
+}
+
+impl<T> Future for Receiver<T> {
+    type Output = Result<T, Canceled>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Result<T, Canceled>> {
+        self.inner.recv(ctx)
+    }
+}
+
+impl<T> FusedFuture for Receiver<T> {
+    fn is_terminated(&self) -> bool {
+        if self.inner.complete.load(SeqCst) {
+            if let Some(slot) = self.inner.data.try_lock() {
+                if slot.is_some() {
+                    return false;
+                }
+            }
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.inner.drop_rx()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver")
+            .field("complete", &self.inner.complete)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::{channel, Canceled};
+    use core::pin::Pin;
+    use futures_core::future::{FusedFuture, Future};
+    use futures_core::task::{Context, Poll, Waker};
+    use std::sync::Arc;
+    use std::task::Wake;
+    use std::thread::{self, Thread};
+
+    struct ThreadWaker(Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    fn block_on<F: Future + Unpin>(mut fut: F) -> F::Output {
+        let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+        let mut ctx = Context::from_waker(&waker);
+        loop {
+            match Pin::new(&mut fut).poll(&mut ctx) {
+                Poll::Ready(t) => return t,
+                Poll::Pending => thread::park(),
+            }
+        }
+    }
+
+    #[test]
+    fn send_across_threads() {
+        let (tx, rx) = channel();
+        let handle = thread::spawn(move || tx.send(42).unwrap());
+        assert_eq!(block_on(rx), Ok(42));
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn sender_drop_cancels() {
+        let (tx, mut rx) = channel::<i32>();
+        let mut ctx = Context::from_waker(Waker::noop());
+        assert_eq!(Pin::new(&mut rx).poll(&mut ctx), Poll::Pending);
+        drop(tx);
+        assert!(rx.is_terminated());
+        assert_eq!(Pin::new(&mut rx).poll(&mut ctx), Poll::Ready(Err(Canceled)));
+    }
+
+    #[test]
+    fn try_recv() {
+        let (tx, mut rx) = channel();
+        assert_eq!(rx.try_recv(), Ok(None));
+        tx.send(1).unwrap();
+        assert!(!rx.is_terminated());
+        assert_eq!(rx.try_recv(), Ok(Some(1)));
+        assert_eq!(rx.try_recv(), Err(Canceled));
+    }
+
+    #[test]
+    fn close_rx() {
+        let (mut tx, mut rx) = channel();
+        rx.close();
+        assert!(tx.is_canceled());
+        let mut ctx = Context::from_waker(Waker::noop());
+        assert_eq!(tx.poll_canceled(&mut ctx), Poll::Ready(()));
+        assert_eq!(tx.send(1), Err(1));
+        assert_eq!(rx.try_recv(), Err(Canceled));
+    }
+
+    #[test]
+    fn receiver_drop_wakes_sender() {
+        let (mut tx, rx) = channel::<()>();
+        let handle = thread::spawn(move || drop(rx));
+        block_on(tx.cancellation());
+        handle.join().unwrap();
+        assert!(tx.is_canceled());
+    }
+
+    #[test]
+    fn select_over_receivers() {
+        let (tx_a, mut a) = channel();
+        let (tx_b, mut b) = channel();
+        tx_a.send(1).unwrap();
+        let handle = thread::spawn(move || tx_b.send(2).unwrap());
+
+        let total = block_on(core::pin::pin!(async {
+            let mut total = 0;
+            loop {
+                futures_core::select! {
+                    x = a => total += x.unwrap(),
+                    y = b => total += y.unwrap(),
+                    complete => break total,
+                }
+            }
+        }));
+        assert_eq!(total, 3);
+        handle.join().unwrap();
+    }
+}
@@ -35,6 +35,12 @@ where
     }
 }
 
+impl<F: FusedFuture> FusedFuture for core::panic::AssertUnwindSafe<F> {
+    fn is_terminated(&self) -> bool {
+        <F as FusedFuture>::is_terminated(&**self)
+    }
+}
+
 mod private_try_future {
     use super::Future;
     pub trait Sealed {}
@@ -76,10 +82,4 @@ mod if_alloc {
             <F as FusedFuture>::is_terminated(&**self)
         }
     }
-
-    impl<F: FusedFuture> FusedFuture for std::panic::AssertUnwindSafe<F> {
-        fn is_terminated(&self) -> bool {
-            <F as FusedFuture>::is_terminated(&**self)
-        }
-    }
 }
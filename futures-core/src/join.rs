@@ -0,0 +1,239 @@
+//! The `join!` and `try_join!` macros.
+
+use crate::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+/// A future that keeps its output once it completed, until it is taken.
+#[doc(hidden)]
+#[derive(Debug)]
+pub enum MaybeDone<Fut: Future> {
+    Future(Fut),
+    Done(Fut::Output),
+    Gone,
+}
+
+impl<Fut: Future> MaybeDone<Fut> {
+    /// Polls the future if it hasn't completed yet, and returns whether it
+    /// has.
+    pub fn poll_done(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> bool {
+        // SAFETY: the future is never moved out of the pinned variant, it is
+        // only dropped in place by replacing it with `Done`.
+        let this = unsafe { self.get_unchecked_mut() };
+        match this {
+            Self::Future(f) => match unsafe { Pin::new_unchecked(f) }.poll(ctx) {
+                Poll::Ready(output) => {
+                    *this = Self::Done(output);
+                    true
+                }
+                Poll::Pending => false,
+            },
+            Self::Done(_) => true,
+            Self::Gone => panic!("MaybeDone polled after its output was taken"),
+        }
+    }
+
+    /// Takes the output out of a completed future.
+    pub fn take_output(self: Pin<&mut Self>) -> Option<Fut::Output> {
+        // SAFETY: only the `Done` variant, which holds no pinned data, is
+        // moved out of.
+        let this = unsafe { self.get_unchecked_mut() };
+        match this {
+            Self::Done(_) => match core::mem::replace(this, Self::Gone) {
+                Self::Done(output) => Some(output),
+                _ => unreachable!(),
+            },
+            _ => None,
+        }
+    }
+}
+
+impl<Fut, T, E> MaybeDone<Fut>
+where
+    Fut: Future<Output = Result<T, E>>,
+{
+    /// Takes the error out of a future that completed with one, leaving
+    /// successful outputs in place.
+    pub fn take_err(mut self: Pin<&mut Self>) -> Option<E> {
+        match &*self {
+            Self::Done(Err(_)) => match self.as_mut().take_output() {
+                Some(Err(e)) => Some(e),
+                _ => unreachable!(),
+            },
+            _ => None,
+        }
+    }
+
+    /// Takes the value out of a future that completed successfully.
+    pub fn take_ok(self: Pin<&mut Self>) -> Option<T> {
+        self.take_output().and_then(Result::ok)
+    }
+}
+
+/// Polls multiple futures simultaneously, returning a tuple of all results
+/// once complete.
+///
+/// `join!` is similar to `.await`ing each future in turn, but the futures
+/// are polled concurrently on the current task. It can only be used inside
+/// of async functions, closures, and blocks.
+///
+/// ```
+/// # use core::future::Future;
+/// # let fut = core::pin::pin!(async {
+/// use futures_core::join;
+///
+/// let a = async { 1 };
+/// let b = async { "two" };
+/// assert_eq!(join!(a, b), (1, "two"));
+/// # });
+/// # assert!(fut.poll(&mut futures_core::task::noop_context()).is_ready());
+/// ```
+#[macro_export]
+macro_rules! join {
+    // All futures are normalized: each one is paired with one `_` per future
+    // before it, which is used to pick it out of the tuple of futures.
+    (@ { ( $($count:tt)* ) $( ( $($skip:tt)* ) $e:expr, )* }) => {{
+        let mut __futures = ( $( $crate::__private::MaybeDone::Future($e), )* );
+        let __futures = &mut __futures;
+        $crate::__private::poll_fn(|__ctx| {
+            let mut __done = true;
+            $(
+                let ( $($skip,)* __fut, .. ) = &mut *__futures;
+                // SAFETY: the tuple lives on the stack of the enclosing async
+                // block and is only accessed through this borrow.
+                let __fut = unsafe { $crate::__private::Pin::new_unchecked(__fut) };
+                __done &= __fut.poll_done(__ctx);
+            )*
+            if !__done {
+                return $crate::task::Poll::Pending;
+            }
+            $crate::task::Poll::Ready(( $({
+                let ( $($skip,)* __fut, .. ) = &mut *__futures;
+                let __fut = unsafe { $crate::__private::Pin::new_unchecked(__fut) };
+                __fut.take_output().expect("`join!` output taken twice")
+            },)* ))
+        }).await
+    }};
+
+    (@ { ( $($count:tt)* ) $($t:tt)* } $e:expr, $($rest:tt)*) => {
+        $crate::join!(@ { ( $($count)* _ ) $($t)* ( $($count)* ) $e, } $($rest)*)
+    };
+
+    () => { () };
+
+    ( $($e:expr),+ $(,)? ) => {
+        $crate::join!(@ { () } $($e,)*)
+    };
+}
+
+/// Polls multiple futures that return `Result`s simultaneously, returning
+/// a tuple of all successful values once complete, or the first error.
+///
+/// Unlike [`join!`], this stops as soon as one future fails, and drops the
+/// others. All futures must have the same error type.
+///
+/// ```
+/// # use core::future::Future;
+/// # let fut = core::pin::pin!(async {
+/// use futures_core::try_join;
+///
+/// let a = async { Ok::<_, &str>(1) };
+/// let b = async { Err::<u8, _>("failed") };
+/// assert_eq!(try_join!(a, b), Err("failed"));
+/// # });
+/// # assert!(fut.poll(&mut futures_core::task::noop_context()).is_ready());
+/// ```
+#[macro_export]
+macro_rules! try_join {
+    (@ { ( $($count:tt)* ) $( ( $($skip:tt)* ) $e:expr, )* }) => {{
+        let mut __futures = ( $( $crate::__private::MaybeDone::Future($e), )* );
+        let __futures = &mut __futures;
+        $crate::__private::poll_fn(|__ctx| {
+            let mut __done = true;
+            $(
+                let ( $($skip,)* __fut, .. ) = &mut *__futures;
+                // SAFETY: as in `join!`.
+                let mut __fut = unsafe { $crate::__private::Pin::new_unchecked(__fut) };
+                if __fut.as_mut().poll_done(__ctx) {
+                    if let ::core::option::Option::Some(e) = __fut.take_err() {
+                        return $crate::task::Poll::Ready(::core::result::Result::Err(e));
+                    }
+                } else {
+                    __done = false;
+                }
+            )*
+            if !__done {
+                return $crate::task::Poll::Pending;
+            }
+            $crate::task::Poll::Ready(::core::result::Result::Ok(( $({
+                let ( $($skip,)* __fut, .. ) = &mut *__futures;
+                let __fut = unsafe { $crate::__private::Pin::new_unchecked(__fut) };
+                __fut.take_ok().expect("`try_join!` output taken twice")
+            },)* )))
+        }).await
+    }};
+
+    (@ { ( $($count:tt)* ) $($t:tt)* } $e:expr, $($rest:tt)*) => {
+        $crate::try_join!(@ { ( $($count)* _ ) $($t)* ( $($count)* ) $e, } $($rest)*)
+    };
+
+    ( $($e:expr),+ $(,)? ) => {
+        $crate::try_join!(@ { () } $($e,)*)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::block_on;
+    use core::cell::Cell;
+    use core::future::{poll_fn, ready};
+    use core::task::Poll;
+
+    /// Returns `Pending` once before completing with `v`.
+    async fn yield_then<T>(v: T) -> T {
+        let mut yielded = false;
+        poll_fn(|ctx| {
+            if yielded {
+                Poll::Ready(())
+            } else {
+                yielded = true;
+                ctx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        })
+        .await;
+        v
+    }
+
+    #[test]
+    fn join_waits_for_all() {
+        let out = block_on(async { join!(yield_then(1), ready("a"), yield_then(yield_then(2.5))) });
+        assert_eq!(out.0, 1);
+        assert_eq!(out.1, "a");
+        assert_eq!(block_on(out.2), 2.5);
+        block_on(async {
+            let () = join!();
+            assert_eq!(join!(ready(3),), (3,));
+        });
+    }
+
+    #[test]
+    fn try_join_short_circuits() {
+        let polled = Cell::new(false);
+        let res = block_on(async {
+            try_join!(
+                async {
+                    yield_then(()).await;
+                    polled.set(true);
+                    Ok::<u8, &str>(1)
+                },
+                ready(Err::<u8, _>("boom")),
+            )
+        });
+        assert_eq!(res, Err("boom"));
+        assert!(!polled.get());
+
+        let res = block_on(async { try_join!(yield_then(Ok::<_, ()>(1)), ready(Ok("b"))) });
+        assert_eq!(res, Ok((1, "b")));
+    }
+}
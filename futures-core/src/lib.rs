@@ -1,5 +1,14 @@
+//! Core traits and types for asynchronous operations.
+//!
+//! Everything outside of the `alloc`-gated boxed aliases and impls works
+//! without the standard library. The `std` feature is enabled by default.
+
+#![no_std]
+
 #[cfg(feature = "alloc")]
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
 pub mod future;
 
@@ -10,5 +19,53 @@ pub mod stream;
 #[doc(no_inline)]
 pub use self::stream::{FusedStream, Stream, TryStream};
 
+pub mod sink;
+#[doc(no_inline)]
+pub use self::sink::Sink;
+
 #[macro_use]
 pub mod task;
+
+mod join;
+mod select;
+
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::join::MaybeDone;
+    pub use crate::select::{assert_unpin, random_start, SelectBranch};
+    pub use core::future::poll_fn;
+    pub use core::pin::Pin;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::task::{waker, ArcWake};
+    use core::future::Future;
+    use core::pin::pin;
+    use core::task::{Context, Poll};
+    use std::sync::Arc;
+    use std::thread::{self, Thread};
+
+    struct Unpark(Thread);
+
+    impl ArcWake for Unpark {
+        fn wake_by_ref(arc_self: &Arc<Self>) {
+            arc_self.0.unpark();
+        }
+    }
+
+    /// Runs `fut` to completion on the current thread, parking it while the
+    /// future is pending, so that the tests in this crate don't need an
+    /// executor from the crates built on top of it.
+    pub(crate) fn block_on<F: Future>(fut: F) -> F::Output {
+        let waker = waker(Arc::new(Unpark(thread::current())));
+        let mut ctx = Context::from_waker(&waker);
+        let mut fut = pin!(fut);
+        loop {
+            if let Poll::Ready(out) = fut.as_mut().poll(&mut ctx) {
+                return out;
+            }
+            thread::park();
+        }
+    }
+}
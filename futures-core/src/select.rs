@@ -0,0 +1,487 @@
+//! The `select!` and `select_biased!` macros.
+
+use crate::future::FusedFuture;
+use core::pin::Pin;
+use core::sync::atomic::{AtomicU32, Ordering};
+use core::task::{Context, Poll};
+
+/// One branch of a `select!`: the future, and a slot for its output.
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct SelectBranch<Fut: FusedFuture> {
+    future: Fut,
+    output: Option<Fut::Output>,
+}
+
+impl<Fut: FusedFuture> SelectBranch<Fut> {
+    pub fn new(future: Fut) -> Self {
+        Self {
+            future,
+            output: None,
+        }
+    }
+
+    /// Polls the future, unless it already terminated, in which case `None`
+    /// is returned. A ready output is stored in the branch.
+    pub fn poll_branch(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Option<Poll<()>> {
+        // SAFETY: `future` is structurally pinned, `output` is not.
+        let this = unsafe { self.get_unchecked_mut() };
+        if this.future.is_terminated() {
+            return None;
+        }
+        match unsafe { Pin::new_unchecked(&mut this.future) }.poll(ctx) {
+            Poll::Ready(output) => {
+                this.output = Some(output);
+                Some(Poll::Ready(()))
+            }
+            Poll::Pending => Some(Poll::Pending),
+        }
+    }
+
+    pub fn take_output(self: Pin<&mut Self>) -> Option<Fut::Output> {
+        // SAFETY: the output is not pinned.
+        unsafe { self.get_unchecked_mut() }.output.take()
+    }
+}
+
+/// Futures passed to `select!` by name are polled through a borrow, so they
+/// have to be `Unpin`.
+#[doc(hidden)]
+#[inline(always)]
+pub fn assert_unpin<T: Unpin>(_: &T) {}
+
+/// Picks the branch `select!` polls first, so that no branch is starved.
+#[doc(hidden)]
+pub fn random_start(branches: usize) -> usize {
+    // A racy xorshift: lost updates only make the sequence less random.
+    static STATE: AtomicU32 = AtomicU32::new(0x9E37_79B9);
+    let mut x = STATE.load(Ordering::Relaxed);
+    x ^= x << 13;
+    x ^= x >> 17;
+    x ^= x << 5;
+    STATE.store(x, Ordering::Relaxed);
+    if branches == 0 {
+        0
+    } else {
+        x as usize % branches
+    }
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __select {
+    // Parsing. Every branch is normalized to
+    // `( (one `_` per branch before it) pattern, future, body )`, with the
+    // `complete` and `default` bodies kept apart.
+    (@parse $biased:tt $count:tt $branches:tt $complete:tt $default:tt) => {
+        $crate::__select!(@expand $biased $count $branches $complete $default)
+    };
+    (@parse $biased:tt $count:tt $branches:tt $complete:tt $default:tt
+        complete => $($rest:tt)*) => {
+        $crate::__select!(@body $biased $count $branches $complete $default (complete) $($rest)*)
+    };
+    (@parse $biased:tt $count:tt $branches:tt $complete:tt $default:tt
+        default => $($rest:tt)*) => {
+        $crate::__select!(@body $biased $count $branches $complete $default (default) $($rest)*)
+    };
+    (@parse $biased:tt $count:tt $branches:tt $complete:tt $default:tt
+        $p:pat = $fut:ident => $($rest:tt)*) => {
+        $crate::__select!(@body $biased $count $branches $complete $default
+            (branch $p, { $crate::__private::assert_unpin(&$fut); &mut $fut }) $($rest)*)
+    };
+    (@parse $biased:tt $count:tt $branches:tt $complete:tt $default:tt
+        $p:pat = $fut:expr => $($rest:tt)*) => {
+        $crate::__select!(@body $biased $count $branches $complete $default
+            (branch $p, $fut) $($rest)*)
+    };
+
+    (@body $biased:tt $count:tt $branches:tt $complete:tt $default:tt $kind:tt
+        $body:block , $($rest:tt)*) => {
+        $crate::__select!(@push $biased $count $branches $complete $default $kind $body; $($rest)*)
+    };
+    (@body $biased:tt $count:tt $branches:tt $complete:tt $default:tt $kind:tt
+        $body:block $($rest:tt)*) => {
+        $crate::__select!(@push $biased $count $branches $complete $default $kind $body; $($rest)*)
+    };
+    (@body $biased:tt $count:tt $branches:tt $complete:tt $default:tt $kind:tt
+        $body:expr , $($rest:tt)*) => {
+        $crate::__select!(@push $biased $count $branches $complete $default $kind $body; $($rest)*)
+    };
+    (@body $biased:tt $count:tt $branches:tt $complete:tt $default:tt $kind:tt
+        $body:expr) => {
+        $crate::__select!(@push $biased $count $branches $complete $default $kind $body;)
+    };
+
+    (@push $biased:tt ( $($count:tt)* ) [ $($branches:tt)* ] $complete:tt $default:tt
+        (branch $p:pat, $fut:expr) $body:expr; $($rest:tt)*) => {
+        $crate::__select!(@parse $biased ( $($count)* _ )
+            [ $($branches)* ( ( $($count)* ) $p, $fut, $body ) ]
+            $complete $default $($rest)*)
+    };
+    (@push $biased:tt $count:tt $branches:tt [] $default:tt
+        (complete) $body:expr; $($rest:tt)*) => {
+        $crate::__select!(@parse $biased $count $branches [$body] $default $($rest)*)
+    };
+    (@push $biased:tt $count:tt $branches:tt $complete:tt []
+        (default) $body:expr; $($rest:tt)*) => {
+        $crate::__select!(@parse $biased $count $branches $complete [$body] $($rest)*)
+    };
+    (@push $biased:tt $count:tt $branches:tt $complete:tt $default:tt
+        ($kind:ident) $body:expr; $($rest:tt)*) => {
+        ::core::compile_error!(::core::concat!(
+            "`select!` accepts at most one `", ::core::stringify!($kind), "` branch"
+        ))
+    };
+
+    // Expansion. The branches are polled from a closure, which returns
+    // `Ready(true)` once a branch completed and `Ready(false)` if every
+    // branch already terminated. The bodies run afterwards, once the
+    // futures and their borrows are gone, so they may use `break`,
+    // `continue`, `return` and `.await`, and reassign the futures.
+    (@expand $biased:tt ( $($count:tt)* )
+        [ $( ( ( $($skip:tt)* ) $p:pat, $fut:expr, $body:expr ) )* ]
+        [ $($complete:expr)? ] [ $($default:expr)? ]) => {{
+        let (__selected, mut __outputs) = {
+            let mut __branches = ( $( $crate::__private::SelectBranch::new($fut), )* );
+            let mut __poll = |__ctx: &mut $crate::task::Context<'_>| {
+                let __start = $crate::__select!(@start $biased ($($count)*));
+                let mut __any_pending = false;
+                for __pass in 0..2 {
+                    $({
+                        let __index = $crate::__select!(@count $($skip)*);
+                        if (__pass == 0) == (__index >= __start) {
+                            let ( $($skip,)* ref mut __branch, .. ) = __branches;
+                            // SAFETY: the branches live on the stack and are
+                            // never moved until they are dropped.
+                            let __branch = unsafe { $crate::__private::Pin::new_unchecked(__branch) };
+                            match __branch.poll_branch(__ctx) {
+                                ::core::option::Option::Some($crate::task::Poll::Ready(())) => {
+                                    return $crate::task::Poll::Ready(true);
+                                }
+                                ::core::option::Option::Some($crate::task::Poll::Pending) => {
+                                    __any_pending = true;
+                                }
+                                ::core::option::Option::None => {}
+                            }
+                        }
+                    })*
+                }
+                if __any_pending {
+                    $crate::task::Poll::Pending
+                } else {
+                    $crate::task::Poll::Ready(false)
+                }
+            };
+            let __selected = $crate::__select!(@poll __poll $($default)?);
+            let __outputs = ( $({
+                let ( $($skip,)* ref mut __branch, .. ) = __branches;
+                let __branch = unsafe { $crate::__private::Pin::new_unchecked(__branch) };
+                __branch.take_output()
+            },)* );
+            (__selected, __outputs)
+        };
+        let __outputs = &mut __outputs;
+        match __selected {
+            $crate::task::Poll::Ready(true) => {
+                $(
+                    if let ::core::option::Option::Some(__output) = {
+                        let ( $($skip,)* __output, .. ) = &mut *__outputs;
+                        __output.take()
+                    } {
+                        let $p = __output;
+                        $body
+                    } else
+                )* {
+                    ::core::unreachable!()
+                }
+            }
+            $crate::task::Poll::Ready(false) => {
+                $crate::__select!(@complete $($complete)?)
+            }
+            $crate::task::Poll::Pending => {
+                $crate::__select!(@default $($default)?)
+            }
+        }
+    }};
+
+    (@start biased $count:tt) => { 0usize };
+    (@start random ( $($count:tt)* )) => {
+        $crate::__private::random_start($crate::__select!(@count $($count)*))
+    };
+
+    (@count $($t:tt)*) => { 0usize $( + $crate::__select!(@one $t) )* };
+    (@one $t:tt) => { 1usize };
+
+    // Without a `default` branch, wait until a branch is ready; with one,
+    // poll each branch only once.
+    (@poll $poll:ident) => {
+        $crate::task::Poll::Ready($crate::__private::poll_fn($poll).await)
+    };
+    (@poll $poll:ident $default:expr) => {
+        $poll(&mut $crate::task::noop_context())
+    };
+
+    (@complete) => {
+        ::core::panic!(
+            "all futures in `select!` were completed, but no `complete =>` handler was provided"
+        )
+    };
+    (@complete $complete:expr) => { $complete };
+
+    (@default) => { ::core::unreachable!() };
+    (@default $default:expr) => { $default };
+}
+
+/// Polls multiple futures and streams simultaneously, executing the branch
+/// for the future that finishes first.
+///
+/// Each branch has the form `pattern = future => body`. Futures must
+/// implement [`FusedFuture`](crate::future::FusedFuture), so that a future
+/// that already completed is not polled again; streams are selected on with
+/// `stream.next()`, which is fused when the stream implements
+/// [`FusedStream`](crate::stream::FusedStream). A future given by name is
+/// polled in place and must be `Unpin`; any other expression is evaluated
+/// into a temporary that only lives for the `select!`.
+///
+/// When several futures are ready, one is picked at random. Use
+/// [`select_biased!`](crate::select_biased) to poll the branches in order instead.
+///
+/// Two special branches are allowed:
+///
+/// - `complete => body` runs when all futures and streams have terminated.
+///   Without it, `select!` panics in that case.
+/// - `default => body` runs when no future is immediately ready. With it,
+///   `select!` never waits, and can be used outside of `async` code.
+///
+/// ```
+/// # use core::future::Future;
+/// # use core::pin::Pin;
+/// # use core::task::{Context, Poll};
+/// # use futures_core::future::FusedFuture;
+/// # struct Value(Option<u32>);
+/// # impl Future for Value {
+/// #     type Output = u32;
+/// #     fn poll(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<u32> {
+/// #         Poll::Ready(self.0.take().unwrap())
+/// #     }
+/// # }
+/// # impl FusedFuture for Value {
+/// #     fn is_terminated(&self) -> bool {
+/// #         self.0.is_none()
+/// #     }
+/// # }
+/// # fn value(v: u32) -> Value {
+/// #     Value(Some(v))
+/// # }
+/// # let fut = core::pin::pin!(async {
+/// use futures_core::select;
+///
+/// // `value(v)` is a fused future that is ready with `v` right away.
+/// let mut a = value(1);
+/// let mut b = value(2);
+///
+/// let mut total = 0;
+/// loop {
+///     select! {
+///         x = a => total += x,
+///         y = b => total += y,
+///         complete => break,
+///     }
+/// }
+/// assert_eq!(total, 3);
+/// # });
+/// # assert!(fut.poll(&mut futures_core::task::noop_context()).is_ready());
+/// ```
+#[macro_export]
+macro_rules! select {
+    ($($tokens:tt)*) => {
+        $crate::__select!(@parse random () [] [] [] $($tokens)*)
+    };
+}
+
+/// Polls multiple futures and streams simultaneously, executing the branch
+/// for the future that finishes first, preferring earlier branches.
+///
+/// This is [`select!`], except that the branches are always polled in the
+/// order in which they are written. A branch that is always ready can starve
+/// the ones after it.
+#[macro_export]
+macro_rules! select_biased {
+    ($($tokens:tt)*) => {
+        $crate::__select!(@parse biased () [] [] [] $($tokens)*)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::future::{FusedFuture, Future};
+    use crate::stream::{FusedStream, Stream};
+    use crate::tests::block_on;
+    use core::pin::Pin;
+    use core::task::{Context, Poll};
+    use std::vec::Vec;
+
+    /// A fused future that is ready with `v` after `n` polls.
+    struct After {
+        n: usize,
+        v: u32,
+        done: bool,
+    }
+
+    fn after(n: usize, v: u32) -> After {
+        After { n, v, done: false }
+    }
+
+    impl Future for After {
+        type Output = u32;
+
+        fn poll(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<u32> {
+            if self.n == 0 {
+                self.done = true;
+                Poll::Ready(self.v)
+            } else {
+                self.n -= 1;
+                ctx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        }
+    }
+
+    impl FusedFuture for After {
+        fn is_terminated(&self) -> bool {
+            self.done
+        }
+    }
+
+    /// A fused stream over a vec, one item per poll.
+    struct Items(Vec<u32>);
+
+    impl Stream for Items {
+        type Item = u32;
+
+        fn poll_next(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<u32>> {
+            Poll::Ready(if self.0.is_empty() {
+                None
+            } else {
+                Some(self.0.remove(0))
+            })
+        }
+    }
+
+    impl FusedStream for Items {
+        fn is_terminated(&self) -> bool {
+            self.0.is_empty()
+        }
+    }
+
+    /// `stream.next()` without depending on `StreamExt`.
+    struct Next<'a>(&'a mut Items);
+
+    impl Future for Next<'_> {
+        type Output = Option<u32>;
+
+        fn poll(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Option<u32>> {
+            Pin::new(&mut *self.0).poll_next(ctx)
+        }
+    }
+
+    impl FusedFuture for Next<'_> {
+        fn is_terminated(&self) -> bool {
+            self.0.is_terminated()
+        }
+    }
+
+    #[test]
+    fn select_runs_first_ready_branch() {
+        let out = block_on(async {
+            select! {
+                a = after(3, 1) => a * 10,
+                b = after(1, 2) => { b * 100 }
+            }
+        });
+        assert_eq!(out, 200);
+    }
+
+    #[test]
+    fn select_loop_until_complete() {
+        block_on(async {
+            let mut a = after(2, 1);
+            let mut b = after(0, 2);
+            let mut items = Items(std::vec![5, 6]);
+            let mut seen = Vec::new();
+            loop {
+                select! {
+                    x = a => seen.push(x),
+                    y = b => seen.push(y),
+                    z = Next(&mut items) => seen.extend(z),
+                    complete => break,
+                }
+            }
+            seen.sort();
+            assert_eq!(seen, [1, 2, 5, 6]);
+            assert!(a.is_terminated() && b.is_terminated());
+        });
+    }
+
+    #[test]
+    fn select_biased_prefers_earlier_branches() {
+        block_on(async {
+            let mut order = Vec::new();
+            let mut a = after(0, 1);
+            let mut b = after(0, 2);
+            let mut c = after(0, 3);
+            loop {
+                select_biased! {
+                    x = c => order.push(x),
+                    x = a => order.push(x),
+                    x = b => order.push(x),
+                    complete => break,
+                }
+            }
+            assert_eq!(order, [3, 1, 2]);
+        });
+    }
+
+    #[test]
+    fn select_is_not_always_biased() {
+        let mut first = [0; 2];
+        for _ in 0..64 {
+            let i = block_on(async {
+                select! {
+                    _ = after(0, 0) => 0,
+                    _ = after(0, 0) => 1,
+                }
+            });
+            first[i] += 1;
+        }
+        assert!(first[0] > 0 && first[1] > 0, "{:?}", first);
+    }
+
+    #[test]
+    fn default_runs_when_nothing_is_ready() {
+        let mut fut = after(1, 4);
+        let mut poll = || {
+            select! {
+                v = fut => Some(v),
+                default => None,
+                complete => Some(0),
+            }
+        };
+        assert_eq!(poll(), None);
+        assert_eq!(poll(), Some(4));
+        assert_eq!(poll(), Some(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "no `complete =>` handler")]
+    fn select_panics_when_all_complete_without_handler() {
+        let mut a = after(0, 1);
+        block_on(async {
+            for _ in 0..2 {
+                select! {
+                    _ = a => {},
+                }
+            }
+        });
+    }
+}
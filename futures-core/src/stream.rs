@@ -1,10 +1,10 @@
 //! Asynchronous streams.
 
 use core::{
+    ops::DerefMut,
     pin::Pin,
     task::{Context, Poll},
 };
-use std::ops::DerefMut;
 
 #[cfg(feature = "alloc")]
 pub type BoxStream<'a, T> = Pin<alloc::boxed::Box<dyn Stream<Item = T> + Send + 'a>>;
@@ -53,6 +53,18 @@ where
     }
 }
 
+impl<S: Stream> Stream for core::panic::AssertUnwindSafe<S> {
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        unsafe { self.map_unchecked_mut(|x| &mut x.0) }.poll_next(ctx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
 pub trait FusedStream: Stream {
     fn is_terminated(&self) -> bool;
 }
@@ -113,25 +125,12 @@ mod if_alloc {
     impl<S: ?Sized + Stream + Unpin> Stream for Box<S> {
         type Item = S::Item;
 
-        fn poll_next(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        fn poll_next(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
             Pin::new(&mut **self).poll_next(ctx)
         }
 
         fn size_hint(&self) -> (usize, Option<usize>) {
-            (&**self).size_hint()
-        }
-    }
-
-    #[cfg(feature = "std")]
-    impl<S: Stream> Stream for std::panic::AssertUnwindSafe<S> {
-        type Item = S::Item;
-
-        fn poll_next(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-            unsafe { self.map_unchecked_mut(|x| &mut x.0) }.poll_next(ctx)
-        }
-
-        fn size_hint(&self) -> (usize, Option<usize>) {
-            self.0.size_hint()
+            (**self).size_hint()
         }
     }
 
@@ -35,8 +35,56 @@ use core::task::Waker;
 /// when it is ready.
 ///
 /// ```
-/// use futures::future::Future;
+/// use core::pin::Pin;
+/// use core::sync::atomic::AtomicBool;
+/// use core::sync::atomic::Ordering::Relaxed;
+/// use futures_core::future::Future;
+/// use futures_core::task::__internal::AtomicWaker;
+/// use futures_core::task::{Context, Poll};
+/// use std::sync::Arc;
 ///
+/// struct Inner {
+///     waker: AtomicWaker,
+///     set: AtomicBool,
+/// }
+///
+/// #[derive(Clone)]
+/// struct Flag(Arc<Inner>);
+///
+/// impl Flag {
+///     pub fn new() -> Self {
+///         Self(Arc::new(Inner {
+///             waker: AtomicWaker::new(),
+///             set: AtomicBool::new(false),
+///         }))
+///     }
+///
+///     pub fn signal(&self) {
+///         self.0.set.store(true, Relaxed);
+///         self.0.waker.wake();
+///     }
+/// }
+///
+/// impl Future for Flag {
+///     type Output = ();
+///
+///     fn poll(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<()> {
+///         // quick check to avoid registration if already done.
+///         if self.0.set.load(Relaxed) {
+///             return Poll::Ready(());
+///         }
+///
+///         self.0.waker.register(ctx.waker());
+///
+///         // Need to check condition **after** `register` to avoid a race
+///         // condition that would result in lost notifications.
+///         if self.0.set.load(Relaxed) {
+///             Poll::Ready(())
+///         } else {
+///             Poll::Pending
+///         }
+///     }
+/// }
 /// ```
 pub struct AtomicWaker {
     state: AtomicUsize,
@@ -54,6 +102,7 @@ const WAKING: usize = 0b10;
 impl AtomicWaker {
     pub const fn new() -> Self {
         // Make sure that task is `Sync`
+        #[allow(dead_code)]
         trait AssertAsync: Sync {}
         impl AssertAsync for Waker {}
 
@@ -141,11 +190,14 @@ mod tests {
     use std::sync::atomic::{AtomicUsize, Ordering::*};
     use std::{
         future::Future,
+        println,
         sync::{atomic::AtomicBool, Arc},
         task::Poll,
+        vec,
     };
 
     use super::AtomicWaker;
+    use crate::task::{waker, ArcWake};
     struct Inner {
         waker: AtomicWaker,
         set: AtomicBool,
@@ -188,6 +240,48 @@ mod tests {
             }
         }
     }
+
+    struct CountingWaker(AtomicUsize);
+
+    impl ArcWake for CountingWaker {
+        fn wake_by_ref(arc_self: &Arc<Self>) {
+            arc_self.0.fetch_add(1, SeqCst);
+        }
+    }
+
+    #[test]
+    fn flag_wakes_registered_task() {
+        let counter = Arc::new(CountingWaker(AtomicUsize::new(0)));
+        let waker = waker(counter.clone());
+        let mut ctx = std::task::Context::from_waker(&waker);
+
+        let mut flag = Flag::new();
+        assert_eq!(std::pin::Pin::new(&mut flag).poll(&mut ctx), Poll::Pending);
+        assert_eq!(counter.0.load(SeqCst), 0);
+
+        let signaller = flag.clone();
+        std::thread::spawn(move || signaller.signal())
+            .join()
+            .unwrap();
+        assert_eq!(counter.0.load(SeqCst), 1);
+        assert_eq!(
+            std::pin::Pin::new(&mut flag).poll(&mut ctx),
+            Poll::Ready(())
+        );
+    }
+
+    #[test]
+    fn flag_can_be_awaited() {
+        let flag = Flag::new();
+        let signaller = flag.clone();
+        let handle = std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(20));
+            signaller.signal();
+        });
+        crate::tests::block_on(flag);
+        handle.join().unwrap();
+    }
+
     #[test]
     fn test_memory_ordering() {
         let a = Arc::new(AtomicUsize::new(0));
@@ -2,4 +2,4 @@
 mod atomic_waker;
 
 #[cfg(not(futures_no_atomic_cas))]
-pub use self::atomic_waker::AtomicWaker;
\ No newline at end of file
+pub use self::atomic_waker::AtomicWaker;
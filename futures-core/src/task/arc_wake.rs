@@ -0,0 +1,129 @@
+use alloc::sync::Arc;
+use core::mem::{self, ManuallyDrop};
+use core::task::{RawWaker, RawWakerVTable, Waker};
+
+/// A way of waking up a specific task.
+///
+/// By implementing this trait, types that are expected to be wrapped in an
+/// `Arc` can be converted into [`Waker`] objects with [`waker`] and
+/// [`waker_ref`](super::waker_ref). Those wakers can be used to signal
+/// executors that a task is ready to be `poll`ed again.
+///
+/// `Send + Sync` are required because the resulting `Waker` may be sent to
+/// and woken from any thread.
+pub trait ArcWake: Send + Sync {
+    /// Indicates that the associated task is ready to make progress and
+    /// should be `poll`ed.
+    ///
+    /// The default implementation forwards to
+    /// [`wake_by_ref`](ArcWake::wake_by_ref). Implementors that can reuse the
+    /// `Arc` may override it to avoid a clone.
+    fn wake(self: Arc<Self>) {
+        Self::wake_by_ref(&self)
+    }
+
+    /// Indicates that the associated task is ready to make progress and
+    /// should be `poll`ed, without consuming the reference.
+    fn wake_by_ref(arc_self: &Arc<Self>);
+}
+
+/// Creates a [`Waker`] from an `Arc<impl ArcWake>`.
+///
+/// The returned waker calls [`ArcWake::wake`] or [`ArcWake::wake_by_ref`]
+/// when woken, and owns one reference count of the `Arc`.
+pub fn waker<W>(wake: Arc<W>) -> Waker
+where
+    W: ArcWake + 'static,
+{
+    let ptr = Arc::into_raw(wake).cast::<()>();
+
+    // SAFETY: the vtable upholds the `RawWaker` contract for an `Arc<W>`.
+    unsafe { Waker::from_raw(RawWaker::new(ptr, waker_vtable::<W>())) }
+}
+
+pub(super) fn waker_vtable<W: ArcWake + 'static>() -> &'static RawWakerVTable {
+    &RawWakerVTable::new(
+        clone_arc_raw::<W>,
+        wake_arc_raw::<W>,
+        wake_by_ref_arc_raw::<W>,
+        drop_arc_raw::<W>,
+    )
+}
+
+unsafe fn clone_arc_raw<W: ArcWake + 'static>(data: *const ()) -> RawWaker {
+    // Bump the refcount without dropping either handle.
+    let arc = ManuallyDrop::new(unsafe { Arc::<W>::from_raw(data.cast::<W>()) });
+    mem::forget(Arc::clone(&arc));
+    RawWaker::new(data, waker_vtable::<W>())
+}
+
+unsafe fn wake_arc_raw<W: ArcWake + 'static>(data: *const ()) {
+    let arc = unsafe { Arc::<W>::from_raw(data.cast::<W>()) };
+    ArcWake::wake(arc);
+}
+
+unsafe fn wake_by_ref_arc_raw<W: ArcWake + 'static>(data: *const ()) {
+    let arc = ManuallyDrop::new(unsafe { Arc::<W>::from_raw(data.cast::<W>()) });
+    ArcWake::wake_by_ref(&arc);
+}
+
+unsafe fn drop_arc_raw<W: ArcWake + 'static>(data: *const ()) {
+    drop(unsafe { Arc::<W>::from_raw(data.cast::<W>()) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{waker, ArcWake};
+    use crate::task::waker_ref;
+    use alloc::sync::Arc;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    struct Counter {
+        wakes: AtomicUsize,
+    }
+
+    impl ArcWake for Counter {
+        fn wake_by_ref(arc_self: &Arc<Self>) {
+            arc_self.wakes.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    fn counter() -> Arc<Counter> {
+        Arc::new(Counter {
+            wakes: AtomicUsize::new(0),
+        })
+    }
+
+    #[test]
+    fn waker_owns_a_reference() {
+        let count = counter();
+        let w = waker(count.clone());
+        assert_eq!(Arc::strong_count(&count), 2);
+
+        let w2 = w.clone();
+        assert_eq!(Arc::strong_count(&count), 3);
+        w2.wake();
+        assert_eq!(Arc::strong_count(&count), 2);
+
+        w.wake_by_ref();
+        assert_eq!(count.wakes.load(Ordering::SeqCst), 2);
+        drop(w);
+        assert_eq!(Arc::strong_count(&count), 1);
+    }
+
+    #[test]
+    fn waker_ref_does_not_touch_refcount() {
+        let count = counter();
+        {
+            let w = waker_ref(&count);
+            assert_eq!(Arc::strong_count(&count), 1);
+            w.wake_by_ref();
+
+            let owned = w.clone();
+            assert_eq!(Arc::strong_count(&count), 2);
+            owned.wake();
+        }
+        assert_eq!(Arc::strong_count(&count), 1);
+        assert_eq!(count.wakes.load(Ordering::SeqCst), 2);
+    }
+}
@@ -2,9 +2,36 @@
 
 #[macro_use]
 mod poll;
+pub use self::poll::{PollExt, PollOptionExt};
+
+#[doc(no_inline)]
+pub use crate::{ready, ready_ok, ready_some};
 
 #[doc(hidden)]
 pub mod __internal;
 
 #[doc(no_inline)]
-pub use core::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
\ No newline at end of file
+pub use core::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+mod spawn;
+pub use self::spawn::{LocalSpawn, Spawn, SpawnError};
+
+mod future_obj;
+pub use self::future_obj::{FutureObj, LocalFutureObj, UnsafeFutureObj};
+
+mod noop_waker;
+pub use self::noop_waker::{noop_context, noop_waker, noop_waker_ref};
+
+#[cfg(not(futures_no_atomic_cas))]
+#[cfg(feature = "alloc")]
+mod arc_wake;
+#[cfg(not(futures_no_atomic_cas))]
+#[cfg(feature = "alloc")]
+pub use self::arc_wake::{waker, ArcWake};
+
+#[cfg(not(futures_no_atomic_cas))]
+#[cfg(feature = "alloc")]
+mod waker_ref;
+#[cfg(not(futures_no_atomic_cas))]
+#[cfg(feature = "alloc")]
+pub use self::waker_ref::{waker_ref, WakerRef};
@@ -0,0 +1,44 @@
+//! Utilities for creating wakers that don't do anything.
+
+use core::task::{Context, Waker};
+
+/// Create a new [`Waker`] which does nothing when `wake()` is called on it.
+#[inline]
+pub fn noop_waker() -> Waker {
+    Waker::noop().clone()
+}
+
+/// Get a static reference to a [`Waker`] which does nothing when `wake()` is
+/// called on it.
+#[inline]
+pub fn noop_waker_ref() -> &'static Waker {
+    Waker::noop()
+}
+
+/// Create a [`Context`] whose waker does nothing, for polling futures by
+/// hand, such as in tests.
+#[inline]
+pub fn noop_context() -> Context<'static> {
+    Context::from_waker(Waker::noop())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{noop_context, noop_waker, noop_waker_ref};
+    use core::future::{pending, Future};
+    use core::pin::pin;
+    use core::task::Poll;
+
+    #[test]
+    fn noop_wakers_can_be_woken() {
+        noop_waker().wake();
+        noop_waker_ref().wake_by_ref();
+    }
+
+    #[test]
+    fn noop_context_polls() {
+        let mut ctx = noop_context();
+        assert_eq!(pin!(pending::<()>()).poll(&mut ctx), Poll::Pending);
+        assert_eq!(pin!(async { 3 }).poll(&mut ctx), Poll::Ready(3));
+    }
+}
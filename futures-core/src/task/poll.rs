@@ -1,12 +1,174 @@
+use core::task::Poll;
+
 /// Extracts the successful type of a `Poll<T>`.
-/// 
+///
 /// This macro bakes in propagation of `Pending` signals by returning early.
 #[macro_export]
 macro_rules! ready {
     ($e:expr $(,)?) => {
         match $e {
-            $crate::task::Poll::Ready(t) -> t,
+            $crate::task::Poll::Ready(t) => t,
             $crate::task::Poll::Pending => return $crate::task::Poll::Pending,
         }
     };
-}
\ No newline at end of file
+}
+
+/// Extracts the successful value of a `Poll<Result<T, E>>`.
+///
+/// `Pending` is propagated by returning early, and so is an error, which is
+/// converted with `From` the same way the `?` operator does.
+#[macro_export]
+macro_rules! ready_ok {
+    ($e:expr $(,)?) => {
+        match $e {
+            $crate::task::Poll::Ready(::core::result::Result::Ok(t)) => t,
+            $crate::task::Poll::Ready(::core::result::Result::Err(e)) => {
+                return $crate::task::Poll::Ready(::core::result::Result::Err(
+                    ::core::convert::From::from(e),
+                ))
+            }
+            $crate::task::Poll::Pending => return $crate::task::Poll::Pending,
+        }
+    };
+}
+
+/// Extracts the item of a `Poll<Option<T>>`, as returned by
+/// `Stream::poll_next`.
+///
+/// `Pending` is propagated by returning early, and so is the end of the
+/// stream, as `Poll::Ready(None)`.
+#[macro_export]
+macro_rules! ready_some {
+    ($e:expr $(,)?) => {
+        match $e {
+            $crate::task::Poll::Ready(::core::option::Option::Some(t)) => t,
+            $crate::task::Poll::Ready(::core::option::Option::None) => {
+                return $crate::task::Poll::Ready(::core::option::Option::None)
+            }
+            $crate::task::Poll::Pending => return $crate::task::Poll::Pending,
+        }
+    };
+}
+
+mod private_poll {
+    use core::task::Poll;
+
+    pub trait Sealed {}
+
+    impl<T> Sealed for Poll<T> {}
+}
+
+/// Helpers for combining `Poll` values, complementing the `map` family that
+/// `Poll` already provides.
+pub trait PollExt<T>: private_poll::Sealed {
+    /// Converts `Ready(t)` into `Some(t)` and `Pending` into `None`.
+    fn into_ready(self) -> Option<T>;
+
+    /// Chains a computation that may itself be pending onto a ready value.
+    fn and_then<U, F>(self, f: F) -> Poll<U>
+    where
+        F: FnOnce(T) -> Poll<U>;
+}
+
+impl<T> PollExt<T> for Poll<T> {
+    #[inline]
+    fn into_ready(self) -> Option<T> {
+        match self {
+            Poll::Ready(t) => Some(t),
+            Poll::Pending => None,
+        }
+    }
+
+    #[inline]
+    fn and_then<U, F>(self, f: F) -> Poll<U>
+    where
+        F: FnOnce(T) -> Poll<U>,
+    {
+        match self {
+            Poll::Ready(t) => f(t),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// Helpers for the `Poll<Option<T>>` values produced by streams.
+pub trait PollOptionExt<T>: private_poll::Sealed {
+    /// Maps the item of a `Ready(Some(t))`, leaving `Ready(None)` and
+    /// `Pending` untouched.
+    fn map_some<U, F>(self, f: F) -> Poll<Option<U>>
+    where
+        F: FnOnce(T) -> U;
+}
+
+impl<T> PollOptionExt<T> for Poll<Option<T>> {
+    #[inline]
+    fn map_some<U, F>(self, f: F) -> Poll<Option<U>>
+    where
+        F: FnOnce(T) -> U,
+    {
+        self.map(|opt| opt.map(f))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PollExt, PollOptionExt};
+    use core::task::Poll;
+
+    fn add_one(p: Poll<u8>) -> Poll<u8> {
+        let t = ready!(p);
+        Poll::Ready(t + 1)
+    }
+
+    fn add_one_ok(p: Poll<Result<u8, u8>>) -> Poll<Result<u8, u16>> {
+        let t = ready_ok!(p);
+        Poll::Ready(Ok(t + 1))
+    }
+
+    fn add_one_some(p: Poll<Option<u8>>) -> Poll<Option<u8>> {
+        let t = ready_some!(p,);
+        Poll::Ready(Some(t + 1))
+    }
+
+    #[test]
+    fn ready_expansion() {
+        assert_eq!(add_one(Poll::Ready(1)), Poll::Ready(2));
+        assert_eq!(add_one(Poll::Pending), Poll::Pending);
+    }
+
+    #[test]
+    fn ready_ok_expansion() {
+        assert_eq!(add_one_ok(Poll::Ready(Ok(1))), Poll::Ready(Ok(2)));
+        assert_eq!(add_one_ok(Poll::Ready(Err(7))), Poll::Ready(Err(7u16)));
+        assert_eq!(add_one_ok(Poll::Pending), Poll::Pending);
+    }
+
+    #[test]
+    fn ready_some_expansion() {
+        assert_eq!(add_one_some(Poll::Ready(Some(1))), Poll::Ready(Some(2)));
+        assert_eq!(add_one_some(Poll::Ready(None)), Poll::Ready(None));
+        assert_eq!(add_one_some(Poll::Pending), Poll::Pending);
+    }
+
+    #[test]
+    fn poll_helpers() {
+        assert_eq!(Poll::Ready(1).into_ready(), Some(1));
+        assert_eq!(Poll::<u8>::Pending.into_ready(), None);
+        assert_eq!(
+            Poll::Ready(1).and_then(|x| Poll::Ready(x * 2)),
+            Poll::Ready(2)
+        );
+        assert_eq!(
+            Poll::Ready(1).and_then(|_| Poll::<u8>::Pending),
+            Poll::Pending
+        );
+        assert_eq!(
+            Poll::Ready(Some(1)).map_some(|x| x + 1),
+            Poll::Ready(Some(2))
+        );
+        assert_eq!(
+            Poll::<Option<u8>>::Ready(None).map_some(|x| x + 1),
+            Poll::Ready(None)
+        );
+    }
+}
@@ -0,0 +1,31 @@
+//! Executors for running futures to completion.
+//!
+//! [`block_on`] runs a single future on the current thread, parking the
+//! thread while the future is pending. [`LocalPool`] runs many futures,
+//! including ones that are not `Send`, on the thread that drives it.
+//! [`ThreadPool`] runs `Send` futures on a set of work-stealing worker
+//! threads.
+//!
+//! Everything in this crate requires the `std` feature, which is enabled by
+//! default.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "std")]
+mod enter;
+#[cfg(feature = "std")]
+pub use crate::enter::{enter, Enter, EnterError};
+
+#[cfg(feature = "std")]
+mod local_pool;
+#[cfg(feature = "std")]
+pub use crate::local_pool::{block_on, LocalPool, LocalSpawner};
+
+#[cfg(feature = "std")]
+#[doc(no_inline)]
+pub use futures_core::task::SpawnError;
+
+#[cfg(feature = "std")]
+mod thread_pool;
+#[cfg(feature = "std")]
+pub use crate::thread_pool::{ThreadPool, ThreadPoolBuilder};
@@ -0,0 +1,384 @@
+use crate::enter;
+use futures_core::future::Future;
+use futures_core::task::{
+    waker_ref, ArcWake, Context, FutureObj, LocalFutureObj, LocalSpawn, Poll, Spawn, SpawnError,
+};
+use std::cell::RefCell;
+use std::fmt;
+use std::pin::{pin, Pin};
+use std::rc::{Rc, Weak};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, Thread};
+use std::vec::Vec;
+
+/// Wakes the thread that is blocked in an executor.
+pub(crate) struct ThreadNotify {
+    thread: Thread,
+    /// Set by a wake-up, and cleared by the executor before it parks.
+    ///
+    /// `thread::park` may wake up spuriously, so the executor only stops
+    /// parking once this flag was set.
+    unparked: AtomicBool,
+}
+
+impl ArcWake for ThreadNotify {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        // Only the first wake-up since the executor last parked needs to
+        // unpark the thread.
+        if !arc_self.unparked.swap(true, Ordering::Release) {
+            arc_self.thread.unpark();
+        }
+    }
+}
+
+std::thread_local! {
+    static CURRENT_THREAD_NOTIFY: Arc<ThreadNotify> = Arc::new(ThreadNotify {
+        thread: thread::current(),
+        unparked: AtomicBool::new(false),
+    });
+}
+
+/// Repeatedly calls `f` with a context whose waker unparks the current
+/// thread, parking in between until it is woken, until `f` returns `Ready`.
+fn run_executor<T, F: FnMut(&mut Context<'_>) -> Poll<T>>(mut f: F) -> T {
+    let _enter = enter().expect(
+        "cannot execute `LocalPool` executor from within \
+         another executor",
+    );
+
+    CURRENT_THREAD_NOTIFY.with(|thread_notify| {
+        let waker = waker_ref(thread_notify);
+        let mut ctx = Context::from_waker(&waker);
+        loop {
+            if let Poll::Ready(t) = f(&mut ctx) {
+                return t;
+            }
+            while !thread_notify.unparked.swap(false, Ordering::Acquire) {
+                thread::park();
+            }
+        }
+    })
+}
+
+/// Run a future to completion on the current thread.
+///
+/// This function will block the caller until the given future has completed.
+/// The thread is parked while the future is pending and unparked by the
+/// future's waker.
+///
+/// # Panics
+///
+/// Panics if called from within another executor, such as from a task
+/// running on a [`LocalPool`].
+pub fn block_on<F: Future>(f: F) -> F::Output {
+    let mut f = pin!(f);
+    run_executor(|ctx| f.as_mut().poll(ctx))
+}
+
+/// The wake-up state of a single task in a [`LocalPool`].
+struct TaskWaker {
+    /// Whether the task has to be polled on the next pass over the pool.
+    woken: AtomicBool,
+    pool: Arc<ThreadNotify>,
+}
+
+impl ArcWake for TaskWaker {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        arc_self.woken.store(true, Ordering::Release);
+        ArcWake::wake_by_ref(&arc_self.pool);
+    }
+}
+
+struct Task {
+    future: LocalFutureObj<'static, ()>,
+    state: Arc<TaskWaker>,
+}
+
+type Incoming = RefCell<Vec<LocalFutureObj<'static, ()>>>;
+
+/// A single-threaded task pool for polling futures to completion.
+///
+/// This executor allows you to multiplex any number of tasks onto a single
+/// thread. It's appropriate to poll strictly I/O-bound futures that do very
+/// little work in between I/O actions, and it can run futures that are not
+/// `Send`.
+///
+/// Tasks are added with a [`LocalSpawner`], and only make progress while one
+/// of the `run` methods drives the pool. Only tasks that were woken are
+/// polled again.
+pub struct LocalPool {
+    tasks: Vec<Task>,
+    incoming: Rc<Incoming>,
+}
+
+/// A handle to a [`LocalPool`] that implements spawning of `!Send` futures.
+#[derive(Clone)]
+pub struct LocalSpawner {
+    incoming: Weak<Incoming>,
+}
+
+impl LocalPool {
+    /// Create a new, empty pool of tasks.
+    pub fn new() -> Self {
+        Self {
+            tasks: Vec::new(),
+            incoming: Default::default(),
+        }
+    }
+
+    /// Get a clonable handle to the pool as a spawner.
+    pub fn spawner(&self) -> LocalSpawner {
+        LocalSpawner {
+            incoming: Rc::downgrade(&self.incoming),
+        }
+    }
+
+    /// Run all tasks in the pool to completion.
+    ///
+    /// The function will block the calling thread until *all* tasks in the
+    /// pool are complete, including any spawned while running existing
+    /// tasks.
+    pub fn run(&mut self) {
+        run_executor(|_| self.poll_pool())
+    }
+
+    /// Runs all the tasks in the pool until the given future completes.
+    ///
+    /// The given future is polled on the calling thread alongside the tasks
+    /// in the pool. Tasks that haven't completed when it does are left in
+    /// the pool, and resume the next time the pool is run.
+    pub fn run_until<F: Future>(&mut self, future: F) -> F::Output {
+        let mut future = pin!(future);
+        run_executor(|ctx| {
+            if let Poll::Ready(output) = future.as_mut().poll(ctx) {
+                return Poll::Ready(output);
+            }
+            let _ = self.poll_pool();
+            // Running the pool may have woken the future.
+            future.as_mut().poll(ctx)
+        })
+    }
+
+    /// Runs all tasks in the pool and returns once no more progress can be
+    /// made on any of them without waiting for an external event.
+    ///
+    /// Unlike the other `run` methods, this never parks the thread.
+    pub fn run_until_stalled(&mut self) {
+        run_executor(|_| {
+            let _ = self.poll_pool();
+            Poll::Ready(())
+        })
+    }
+
+    /// Polls every task that was woken since it was last polled, until none
+    /// is left to poll.
+    ///
+    /// Returns `Ready` once the pool holds no tasks at all.
+    fn poll_pool(&mut self) -> Poll<()> {
+        let notify = CURRENT_THREAD_NOTIFY.with(Arc::clone);
+        loop {
+            let mut polled = false;
+
+            // Admit tasks spawned since the last pass; they start out woken.
+            self.tasks
+                .extend(self.incoming.borrow_mut().drain(..).map(|future| {
+                    let state = Arc::new(TaskWaker {
+                        woken: AtomicBool::new(true),
+                        pool: notify.clone(),
+                    });
+                    Task { future, state }
+                }));
+
+            let mut i = 0;
+            while i < self.tasks.len() {
+                let task = &mut self.tasks[i];
+                if task.state.woken.swap(false, Ordering::Acquire) {
+                    polled = true;
+                    let waker = waker_ref(&task.state);
+                    let mut ctx = Context::from_waker(&waker);
+                    if Pin::new(&mut task.future).poll(&mut ctx).is_ready() {
+                        drop(self.tasks.swap_remove(i));
+                        continue;
+                    }
+                }
+                i += 1;
+            }
+
+            if self.tasks.is_empty() && self.incoming.borrow().is_empty() {
+                return Poll::Ready(());
+            }
+            if !polled && self.incoming.borrow().is_empty() {
+                return Poll::Pending;
+            }
+        }
+    }
+}
+
+impl Default for LocalPool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for LocalPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalPool")
+            .field("tasks", &self.tasks.len())
+            .finish()
+    }
+}
+
+impl Spawn for LocalSpawner {
+    fn spawn_obj(&self, future: FutureObj<'static, ()>) -> Result<(), SpawnError> {
+        self.spawn_local_obj(future.into())
+    }
+
+    fn status(&self) -> Result<(), SpawnError> {
+        self.status_local()
+    }
+}
+
+impl LocalSpawn for LocalSpawner {
+    fn spawn_local_obj(&self, future: LocalFutureObj<'static, ()>) -> Result<(), SpawnError> {
+        let incoming = self.incoming.upgrade().ok_or_else(SpawnError::shutdown)?;
+        incoming.borrow_mut().push(future);
+        Ok(())
+    }
+
+    fn status_local(&self) -> Result<(), SpawnError> {
+        if self.incoming.strong_count() > 0 {
+            Ok(())
+        } else {
+            Err(SpawnError::shutdown())
+        }
+    }
+}
+
+impl fmt::Debug for LocalSpawner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalSpawner").finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{block_on, LocalPool};
+    use futures_channel::oneshot;
+    use futures_core::task::LocalSpawn;
+    use futures_core::task::{Context, Poll};
+    use futures_util::task::LocalSpawnExt;
+    use std::cell::{Cell, RefCell};
+    use std::future::{pending, poll_fn};
+    use std::rc::Rc;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn block_on_ready_and_cross_thread() {
+        assert_eq!(block_on(async { 1 + 1 }), 2);
+
+        let (tx, rx) = oneshot::channel();
+        let handle = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(20));
+            tx.send(7).unwrap();
+        });
+        assert_eq!(block_on(rx), Ok(7));
+        handle.join().unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "from within another executor")]
+    fn nested_block_on_panics() {
+        block_on(async { block_on(async {}) });
+    }
+
+    #[test]
+    fn run_drives_not_send_tasks() {
+        let mut pool = LocalPool::new();
+        let spawner = pool.spawner();
+        let log = Rc::new(RefCell::new(Vec::new()));
+
+        for i in 0..3 {
+            let log = log.clone();
+            let inner = spawner.clone();
+            spawner
+                .spawn_local(async move {
+                    log.borrow_mut().push(i);
+                    if i == 0 {
+                        // Spawning from within a task is picked up by `run`.
+                        let log = log.clone();
+                        inner
+                            .spawn_local(async move { log.borrow_mut().push(10) })
+                            .unwrap();
+                    }
+                })
+                .unwrap();
+        }
+        pool.run();
+        log.borrow_mut().sort();
+        assert_eq!(*log.borrow(), vec![0, 1, 2, 10]);
+    }
+
+    #[test]
+    fn run_until_leaves_other_tasks() {
+        let mut pool = LocalPool::new();
+        let spawner = pool.spawner();
+        let (tx, rx) = oneshot::channel();
+        spawner.spawn_local(pending()).unwrap();
+        spawner
+            .spawn_local(async move { tx.send(3).unwrap() })
+            .unwrap();
+        assert_eq!(pool.run_until(rx), Ok(3));
+        assert_eq!(format!("{:?}", pool), "LocalPool { tasks: 1 }");
+    }
+
+    #[test]
+    fn run_until_stalled_polls_only_woken_tasks() {
+        let mut pool = LocalPool::new();
+        let spawner = pool.spawner();
+        let polls = Rc::new(Cell::new(0));
+
+        // Yields to the pool three times before completing.
+        let counter = polls.clone();
+        spawner
+            .spawn_local(poll_fn(move |ctx: &mut Context<'_>| {
+                counter.set(counter.get() + 1);
+                if counter.get() < 4 {
+                    ctx.waker().wake_by_ref();
+                    Poll::Pending
+                } else {
+                    Poll::Ready(())
+                }
+            }))
+            .unwrap();
+        let (tx, rx) = oneshot::channel::<()>();
+        let done = Rc::new(Cell::new(false));
+        let flag = done.clone();
+        spawner
+            .spawn_local(async move {
+                let _ = rx.await;
+                flag.set(true);
+            })
+            .unwrap();
+
+        pool.run_until_stalled();
+        assert_eq!(polls.get(), 4);
+        assert!(!done.get());
+        pool.run_until_stalled();
+        assert!(!done.get());
+
+        tx.send(()).unwrap();
+        pool.run_until_stalled();
+        assert!(done.get());
+    }
+
+    #[test]
+    fn spawner_fails_after_pool_dropped() {
+        let pool = LocalPool::new();
+        let spawner = pool.spawner();
+        assert!(spawner.status_local().is_ok());
+        drop(pool);
+        assert!(spawner.spawn_local(async {}).unwrap_err().is_shutdown());
+    }
+}
@@ -0,0 +1,544 @@
+use crate::enter;
+use futures_core::future::Future;
+use futures_core::task::{waker_ref, ArcWake, Context, FutureObj, Poll, Spawn, SpawnError};
+use std::cell::{Cell, UnsafeCell};
+use std::collections::VecDeque;
+use std::fmt;
+use std::io;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex, Weak};
+use std::thread;
+
+/// A general-purpose thread pool for scheduling tasks that poll futures to
+/// completion.
+///
+/// Every worker thread owns a run queue. Tasks woken on a worker are pushed
+/// to that worker's queue, tasks spawned or woken from elsewhere go to a
+/// shared injector queue, and a worker that runs out of work steals from
+/// the others.
+///
+/// `ThreadPool` handles are cheap to clone. The worker threads shut down once
+/// the last handle is dropped and the queued tasks have been run.
+pub struct ThreadPool {
+    shared: Arc<Shared>,
+}
+
+/// Thread pool configuration object.
+pub struct ThreadPoolBuilder {
+    pool_size: usize,
+    stack_size: usize,
+    name_prefix: Option<String>,
+    after_start: Option<Arc<dyn Fn(usize) + Send + Sync>>,
+    before_stop: Option<Arc<dyn Fn(usize) + Send + Sync>>,
+}
+
+struct Shared {
+    injector: Mutex<VecDeque<Arc<Task>>>,
+    workers: Box<[Mutex<VecDeque<Arc<Task>>>]>,
+    /// Workers that found no work wait on `wakeup` while holding `sleep`.
+    sleep: Mutex<()>,
+    wakeup: Condvar,
+    /// The number of `ThreadPool` handles alive.
+    handles: AtomicUsize,
+    shutdown: AtomicBool,
+}
+
+std::thread_local! {
+    /// The pool and index of the worker running on this thread, if any.
+    static WORKER: Cell<Option<(*const Shared, usize)>> = const { Cell::new(None) };
+}
+
+/// The task is neither queued nor running.
+const IDLE: usize = 0;
+/// The task is in a run queue.
+const SCHEDULED: usize = 1;
+/// A worker is polling the task.
+const RUNNING: usize = 2;
+/// The task was woken while being polled, and has to be polled again.
+const NOTIFIED: usize = 3;
+/// The task's future has completed.
+const COMPLETE: usize = 4;
+
+/// A spawned future, together with the state machine that decides who may
+/// touch it.
+///
+/// Like `AtomicWaker`, a single atomic tracks the state: waking a task only
+/// queues it on the transition out of `IDLE`, so a task is in at most one
+/// run queue at a time, and only the worker that moved it to `RUNNING`
+/// accesses the future.
+struct Task {
+    state: AtomicUsize,
+    future: UnsafeCell<Option<FutureObj<'static, ()>>>,
+    pool: Weak<Shared>,
+}
+
+// The future is only accessed by the worker that owns the `RUNNING` state.
+unsafe impl Sync for Task {}
+
+impl ArcWake for Task {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        let mut state = arc_self.state.load(Ordering::Acquire);
+        loop {
+            let next = match state {
+                IDLE => SCHEDULED,
+                RUNNING => NOTIFIED,
+                // Already queued, already going to be re-polled, or done.
+                _ => return,
+            };
+            match arc_self
+                .state
+                .compare_exchange(state, next, Ordering::AcqRel, Ordering::Acquire)
+            {
+                Ok(_) => break,
+                Err(actual) => state = actual,
+            }
+        }
+        if state == IDLE {
+            if let Some(pool) = arc_self.pool.upgrade() {
+                pool.schedule(arc_self.clone());
+            }
+        }
+    }
+}
+
+impl Task {
+    /// Polls the task once. The caller must have taken it off a run queue.
+    fn run(self: Arc<Self>, shared: &Shared) {
+        self.state.store(RUNNING, Ordering::Release);
+        let waker = waker_ref(&self);
+        let mut ctx = Context::from_waker(&waker);
+
+        // SAFETY: this worker owns the `RUNNING` state, and nothing else
+        // touches the future until it leaves that state.
+        let future = unsafe { &mut *self.future.get() };
+        let poll = match future {
+            Some(future) => Pin::new(future).poll(&mut ctx),
+            None => Poll::Ready(()),
+        };
+        if poll.is_ready() {
+            *future = None;
+            self.state.store(COMPLETE, Ordering::Release);
+            return;
+        }
+
+        if self
+            .state
+            .compare_exchange(RUNNING, IDLE, Ordering::AcqRel, Ordering::Acquire)
+            .is_err()
+        {
+            // Woken while running: the waker left queueing to us.
+            self.state.store(SCHEDULED, Ordering::Release);
+            shared.schedule(self);
+        }
+    }
+}
+
+impl Shared {
+    fn schedule(&self, task: Arc<Task>) {
+        let local = WORKER
+            .with(Cell::get)
+            .and_then(|(pool, index)| core::ptr::eq(pool, self).then_some(index));
+        match local {
+            Some(index) => self.workers[index].lock().unwrap().push_back(task),
+            None => self.injector.lock().unwrap().push_back(task),
+        }
+        drop(self.sleep.lock().unwrap());
+        self.wakeup.notify_one();
+    }
+
+    /// Finds the next task for worker `index`: from its own queue first, then
+    /// from the injector, and finally by stealing from another worker.
+    fn find_task(&self, index: usize) -> Option<Arc<Task>> {
+        if let Some(task) = self.workers[index].lock().unwrap().pop_front() {
+            return Some(task);
+        }
+        if let Some(task) = self.injector.lock().unwrap().pop_front() {
+            return Some(task);
+        }
+        let len = self.workers.len();
+        (1..len)
+            .map(|offset| (index + offset) % len)
+            .find_map(|victim| self.workers[victim].lock().unwrap().pop_back())
+    }
+
+    fn has_work(&self) -> bool {
+        !self.injector.lock().unwrap().is_empty()
+            || self.workers.iter().any(|q| !q.lock().unwrap().is_empty())
+    }
+
+    fn work(&self, index: usize) {
+        let _enter = enter().expect("thread pool worker is already in an executor");
+        WORKER.with(|w| w.set(Some((self as *const Shared, index))));
+        loop {
+            if let Some(task) = self.find_task(index) {
+                task.run(self);
+                continue;
+            }
+            let guard = self.sleep.lock().unwrap();
+            if self.has_work() {
+                continue;
+            }
+            if self.shutdown.load(Ordering::Acquire) {
+                break;
+            }
+            drop(self.wakeup.wait(guard).unwrap());
+        }
+        WORKER.with(|w| w.set(None));
+    }
+}
+
+impl ThreadPool {
+    /// Creates a new thread pool with the default configuration.
+    ///
+    /// See documentation for the methods in
+    /// [`ThreadPoolBuilder`](ThreadPoolBuilder) for details on the default
+    /// configuration.
+    pub fn new() -> Result<Self, io::Error> {
+        ThreadPoolBuilder::new().create()
+    }
+
+    /// Create a default thread pool configuration, which can then be
+    /// customized.
+    pub fn builder() -> ThreadPoolBuilder {
+        ThreadPoolBuilder::new()
+    }
+
+    /// Spawns a future that will be run to completion.
+    ///
+    /// Unlike [`Spawn::spawn_obj`], this can't fail: the pool is alive for as
+    /// long as this handle is.
+    pub fn spawn_obj_ok(&self, future: FutureObj<'static, ()>) {
+        let task = Arc::new(Task {
+            state: AtomicUsize::new(SCHEDULED),
+            future: UnsafeCell::new(Some(future)),
+            pool: Arc::downgrade(&self.shared),
+        });
+        self.shared.schedule(task);
+    }
+
+    /// Spawns a task that polls the given future with output `()` to
+    /// completion.
+    ///
+    /// The task is picked up by an idle worker, or stolen by the first
+    /// worker to run out of work.
+    pub fn spawn_ok<Fut>(&self, future: Fut)
+    where
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.spawn_obj_ok(FutureObj::new(Box::new(future)))
+    }
+}
+
+impl Spawn for ThreadPool {
+    fn spawn_obj(&self, future: FutureObj<'static, ()>) -> Result<(), SpawnError> {
+        self.spawn_obj_ok(future);
+        Ok(())
+    }
+}
+
+impl Clone for ThreadPool {
+    fn clone(&self) -> Self {
+        self.shared.handles.fetch_add(1, Ordering::Relaxed);
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl Drop for ThreadPool {
+    fn drop(&mut self) {
+        if self.shared.handles.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.shared.shutdown.store(true, Ordering::Release);
+            drop(self.shared.sleep.lock().unwrap());
+            self.shared.wakeup.notify_all();
+        }
+    }
+}
+
+impl fmt::Debug for ThreadPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ThreadPool")
+            .field("size", &self.shared.workers.len())
+            .finish()
+    }
+}
+
+impl ThreadPoolBuilder {
+    /// Create a default thread pool configuration.
+    ///
+    /// See the other methods on this type for details on the defaults.
+    pub fn new() -> Self {
+        Self {
+            pool_size: thread::available_parallelism().map_or(1, |n| n.get()),
+            stack_size: 0,
+            name_prefix: None,
+            after_start: None,
+            before_stop: None,
+        }
+    }
+
+    /// Set size of a future ThreadPool
+    ///
+    /// The size of a thread pool is the number of worker threads spawned. By
+    /// default, this is equal to the number of CPU cores.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size == 0`.
+    pub fn pool_size(&mut self, size: usize) -> &mut Self {
+        assert!(size > 0);
+        self.pool_size = size;
+        self
+    }
+
+    /// Set stack size of threads in the pool, in bytes.
+    ///
+    /// By default, worker threads use Rust's standard stack size.
+    pub fn stack_size(&mut self, stack_size: usize) -> &mut Self {
+        self.stack_size = stack_size;
+        self
+    }
+
+    /// Set thread name prefix of a future ThreadPool.
+    ///
+    /// Thread name prefix is used for generating thread names. For example,
+    /// if prefix is `my-pool-`, then threads in the pool will get names like
+    /// `my-pool-1` etc.
+    ///
+    /// By default, worker threads are assigned Rust's standard thread name.
+    pub fn name_prefix<S: Into<String>>(&mut self, name_prefix: S) -> &mut Self {
+        self.name_prefix = Some(name_prefix.into());
+        self
+    }
+
+    /// Execute the closure `f` immediately after each worker thread is
+    /// started, but before running any tasks on it.
+    ///
+    /// The closure is given the index of the worker thread.
+    pub fn after_start<F>(&mut self, f: F) -> &mut Self
+    where
+        F: Fn(usize) + Send + Sync + 'static,
+    {
+        self.after_start = Some(Arc::new(f));
+        self
+    }
+
+    /// Execute closure `f` just prior to shutting down each worker thread.
+    ///
+    /// The closure is given the index of the worker thread.
+    pub fn before_stop<F>(&mut self, f: F) -> &mut Self
+    where
+        F: Fn(usize) + Send + Sync + 'static,
+    {
+        self.before_stop = Some(Arc::new(f));
+        self
+    }
+
+    /// Create a [`ThreadPool`](ThreadPool) with the given configuration.
+    ///
+    /// # Errors
+    ///
+    /// Fails if a worker thread can't be spawned. Workers that were already
+    /// spawned shut down.
+    pub fn create(&mut self) -> Result<ThreadPool, io::Error> {
+        let shared = Arc::new(Shared {
+            injector: Mutex::new(VecDeque::new()),
+            workers: (0..self.pool_size)
+                .map(|_| Mutex::new(VecDeque::new()))
+                .collect(),
+            sleep: Mutex::new(()),
+            wakeup: Condvar::new(),
+            handles: AtomicUsize::new(1),
+            shutdown: AtomicBool::new(false),
+        });
+        let pool = ThreadPool { shared };
+        for index in 0..self.pool_size {
+            let shared = pool.shared.clone();
+            let after_start = self.after_start.clone();
+            let before_stop = self.before_stop.clone();
+            let mut thread_builder = thread::Builder::new();
+            if let Some(ref name_prefix) = self.name_prefix {
+                thread_builder = thread_builder.name(format!("{}{}", name_prefix, index));
+            }
+            if self.stack_size > 0 {
+                thread_builder = thread_builder.stack_size(self.stack_size);
+            }
+            thread_builder.spawn(move || {
+                if let Some(after_start) = after_start {
+                    after_start(index);
+                }
+                shared.work(index);
+                if let Some(before_stop) = before_stop {
+                    before_stop(index);
+                }
+            })?;
+        }
+        Ok(pool)
+    }
+}
+
+impl Default for ThreadPoolBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for ThreadPoolBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ThreadPoolBuilder")
+            .field("pool_size", &self.pool_size)
+            .field("stack_size", &self.stack_size)
+            .field("name_prefix", &self.name_prefix)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ThreadPool;
+    use crate::block_on;
+    use futures_channel::{mpsc, oneshot};
+    use futures_util::task::SpawnExt;
+    use futures_util::StreamExt;
+    use std::collections::HashSet;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{mpsc as std_mpsc, Arc, Mutex};
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn runs_spawned_futures() {
+        let pool = ThreadPool::builder().pool_size(3).create().unwrap();
+        let (tx, rx) = mpsc::unbounded();
+        for i in 0..100 {
+            let tx = tx.clone();
+            pool.spawn_ok(async move { tx.unbounded_send(i).unwrap() });
+        }
+        drop(tx);
+        let mut got = block_on(rx.collect::<Vec<_>>());
+        got.sort();
+        assert_eq!(got, (0..100).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn wakes_across_threads() {
+        let pool = ThreadPool::builder().pool_size(2).create().unwrap();
+        let (tx, rx) = oneshot::channel::<u32>();
+        let (done_tx, done_rx) = oneshot::channel();
+        pool.spawn_ok(async move {
+            let v = rx.await.unwrap();
+            done_tx.send(v * 2).unwrap();
+        });
+        thread::sleep(Duration::from_millis(20));
+        tx.send(21).unwrap();
+        assert_eq!(block_on(done_rx), Ok(42));
+    }
+
+    #[test]
+    fn builder_hooks_and_names() {
+        let started = Arc::new(AtomicUsize::new(0));
+        let stopped = Arc::new(AtomicUsize::new(0));
+        let (stop_tx, stop_rx) = std_mpsc::channel();
+        let stop_tx = Mutex::new(stop_tx);
+        let pool = {
+            let started = started.clone();
+            let stopped = stopped.clone();
+            ThreadPool::builder()
+                .pool_size(2)
+                .name_prefix("test-pool-")
+                .stack_size(256 * 1024)
+                .after_start(move |_| {
+                    started.fetch_add(1, Ordering::SeqCst);
+                })
+                .before_stop(move |idx| {
+                    stopped.fetch_add(1, Ordering::SeqCst);
+                    stop_tx.lock().unwrap().send(idx).unwrap();
+                })
+                .create()
+                .unwrap()
+        };
+
+        let (tx, rx) = oneshot::channel();
+        pool.spawn_ok(async move {
+            let name = thread::current().name().map(String::from);
+            tx.send(name).unwrap();
+        });
+        let name = block_on(rx).unwrap().unwrap();
+        assert!(name.starts_with("test-pool-"), "{}", name);
+
+        drop(pool.clone());
+        assert_eq!(stopped.load(Ordering::SeqCst), 0);
+        drop(pool);
+        let mut stopped_idx = vec![
+            stop_rx.recv_timeout(Duration::from_secs(5)).unwrap(),
+            stop_rx.recv_timeout(Duration::from_secs(5)).unwrap(),
+        ];
+        stopped_idx.sort();
+        assert_eq!(stopped_idx, vec![0, 1]);
+        assert_eq!(started.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn idle_workers_steal_local_work() {
+        let pool = ThreadPool::builder()
+            .pool_size(4)
+            .name_prefix("steal-")
+            .create()
+            .unwrap();
+        let threads = Arc::new(Mutex::new(HashSet::new()));
+        let (tx, rx) = mpsc::unbounded();
+        let spawner = pool.clone();
+        let seen = threads.clone();
+        pool.spawn_ok(async move {
+            // Spawned from a worker, these all land in that worker's queue,
+            // so the other workers only run them by stealing.
+            for _ in 0..16 {
+                let tx = tx.clone();
+                let seen = seen.clone();
+                spawner.spawn_ok(async move {
+                    thread::sleep(Duration::from_millis(10));
+                    let name = thread::current().name().unwrap().to_owned();
+                    seen.lock().unwrap().insert(name);
+                    tx.unbounded_send(()).unwrap();
+                });
+            }
+        });
+        assert_eq!(block_on(rx.take(16).collect::<Vec<_>>()).len(), 16);
+        assert!(threads.lock().unwrap().len() > 1);
+    }
+
+    #[test]
+    fn repeated_self_wake_is_polled_again() {
+        let pool = ThreadPool::builder().pool_size(1).create().unwrap();
+        let (tx, rx) = oneshot::channel();
+        pool.spawn_ok(async move {
+            let mut yields = 0;
+            std::future::poll_fn(|ctx| {
+                yields += 1;
+                if yields < 100 {
+                    ctx.waker().wake_by_ref();
+                    std::task::Poll::Pending
+                } else {
+                    std::task::Poll::Ready(())
+                }
+            })
+            .await;
+            tx.send(yields).unwrap();
+        });
+        assert_eq!(block_on(rx), Ok(100));
+    }
+
+    #[test]
+    fn spawn_with_handle() {
+        let pool = ThreadPool::builder().pool_size(2).create().unwrap();
+        let handle = pool.spawn_with_handle(async { 6 * 7 }).unwrap();
+        assert_eq!(block_on(handle), 42);
+
+        let (tx, rx) = oneshot::channel();
+        pool.spawn_with_handle(async move { tx.send(()).unwrap() })
+            .unwrap()
+            .forget();
+        assert_eq!(block_on(rx), Ok(()));
+    }
+}
@@ -0,0 +1,237 @@
+use crate::if_std::{retry, retry_fill_buf};
+use crate::{
+    AsyncBufRead, AsyncRead, AsyncSeek, AsyncWrite, IoSlice, IoSliceMut, Result, SeekFrom,
+};
+use std::fmt;
+use std::io;
+use std::pin::Pin;
+use std::string::String;
+use std::task::{Context, Poll};
+use std::vec::Vec;
+
+/// Wraps a blocking `std::io` type so that it can be used through the
+/// asynchronous I/O traits.
+///
+/// `AllowStdIo<T>` implements [`AsyncRead`], [`AsyncWrite`], [`AsyncSeek`]
+/// and [`AsyncBufRead`] whenever `T` implements the corresponding
+/// `std::io` trait, and the `std::io` traits themselves. Operations are
+/// retried when they are interrupted, and otherwise always complete right
+/// away, blocking the thread if `T` blocks.
+///
+/// That makes it useful for types that never block, like in-memory buffers,
+/// or for code that is fine with blocking the executor. Don't use it for
+/// sockets or pipes on an executor that also runs other work; use the types
+/// of [`net`](crate::net) instead.
+///
+/// ```
+/// use futures_io::{AllowStdIo, AsyncRead};
+/// use std::pin::Pin;
+/// use std::task::{Context, Poll, Waker};
+///
+/// let mut reader = AllowStdIo::new(std::io::Cursor::new(b"hello"));
+/// let mut buf = [0; 8];
+/// let mut ctx = Context::from_waker(Waker::noop());
+/// let res = Pin::new(&mut reader).poll_read(&mut ctx, &mut buf);
+/// assert!(matches!(res, Poll::Ready(Ok(5))));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AllowStdIo<T>(T);
+
+impl<T> Unpin for AllowStdIo<T> {}
+
+impl<T> AllowStdIo<T> {
+    /// Wraps `io`.
+    pub fn new(io: T) -> Self {
+        Self(io)
+    }
+
+    /// Returns a reference to the wrapped value.
+    pub fn get_ref(&self) -> &T {
+        &self.0
+    }
+
+    /// Returns a mutable reference to the wrapped value.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+
+    /// Consumes the wrapper, returning the wrapped value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T: io::Read> io::Read for AllowStdIo<T> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        self.0.read(buf)
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> Result<usize> {
+        self.0.read_vectored(bufs)
+    }
+
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> Result<usize> {
+        self.0.read_to_end(buf)
+    }
+
+    fn read_to_string(&mut self, buf: &mut String) -> Result<usize> {
+        self.0.read_to_string(buf)
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result<()> {
+        self.0.read_exact(buf)
+    }
+}
+
+impl<T: io::Write> io::Write for AllowStdIo<T> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.0.write(buf)
+    }
+
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> Result<usize> {
+        self.0.write_vectored(bufs)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.0.flush()
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> Result<()> {
+        self.0.write_all(buf)
+    }
+
+    fn write_fmt(&mut self, fmt: fmt::Arguments<'_>) -> Result<()> {
+        self.0.write_fmt(fmt)
+    }
+}
+
+impl<T: io::Seek> io::Seek for AllowStdIo<T> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        self.0.seek(pos)
+    }
+}
+
+impl<T: io::BufRead> io::BufRead for AllowStdIo<T> {
+    fn fill_buf(&mut self) -> Result<&[u8]> {
+        self.0.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.0.consume(amt)
+    }
+}
+
+impl<T: io::Read> AsyncRead for AllowStdIo<T> {
+    delegate_async_read_to_stdio!();
+}
+
+impl<T: io::Write> AsyncWrite for AllowStdIo<T> {
+    delegate_async_write_to_stdio!();
+}
+
+impl<T: io::Seek> AsyncSeek for AllowStdIo<T> {
+    delegate_async_seek_to_stdio!();
+}
+
+impl<T: io::BufRead> AsyncBufRead for AllowStdIo<T> {
+    delegate_async_buf_read_to_stdio!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AllowStdIo;
+    use crate::{AsyncBufRead, AsyncRead, AsyncSeek, AsyncWrite, SeekFrom};
+    use std::io::{self, Cursor};
+    use std::pin::Pin;
+    use std::task::{Context, Poll, Waker};
+
+    /// Fails every other call with `Interrupted`.
+    struct Flaky {
+        inner: Cursor<Vec<u8>>,
+        interrupt: bool,
+    }
+
+    impl Flaky {
+        fn interrupt(&mut self) -> io::Result<()> {
+            self.interrupt = !self.interrupt;
+            if self.interrupt {
+                return Err(io::ErrorKind::Interrupted.into());
+            }
+            Ok(())
+        }
+    }
+
+    impl io::Read for Flaky {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.interrupt()?;
+            self.inner.read(buf)
+        }
+    }
+
+    impl io::BufRead for Flaky {
+        fn fill_buf(&mut self) -> io::Result<&[u8]> {
+            self.interrupt()?;
+            self.inner.fill_buf()
+        }
+
+        fn consume(&mut self, amt: usize) {
+            self.inner.consume(amt)
+        }
+    }
+
+    impl io::Write for Flaky {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.interrupt()?;
+            self.inner.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            self.interrupt()
+        }
+    }
+
+    #[test]
+    fn retries_interrupted_operations() {
+        let mut io = AllowStdIo::new(Flaky {
+            inner: Cursor::new(Vec::new()),
+            interrupt: false,
+        });
+        let mut ctx = Context::from_waker(Waker::noop());
+        let res = Pin::new(&mut io).poll_write(&mut ctx, b"abc");
+        assert!(matches!(res, Poll::Ready(Ok(3))));
+        let res = Pin::new(&mut io).poll_flush(&mut ctx);
+        assert!(matches!(res, Poll::Ready(Ok(()))));
+
+        io.get_mut().inner.set_position(1);
+        let mut buf = [0; 4];
+        let res = Pin::new(&mut io).poll_read(&mut ctx, &mut buf);
+        assert!(matches!(res, Poll::Ready(Ok(2))));
+        assert_eq!(&buf[..2], b"bc");
+
+        io.get_mut().inner.set_position(1);
+        match Pin::new(&mut io).poll_fill_buf(&mut ctx) {
+            Poll::Ready(Ok(buf)) => assert_eq!(buf, b"bc"),
+            res => panic!("unexpected {res:?}"),
+        }
+        Pin::new(&mut io).consume(2);
+        match Pin::new(&mut io).poll_fill_buf(&mut ctx) {
+            Poll::Ready(Ok(buf)) => assert!(buf.is_empty()),
+            res => panic!("unexpected {res:?}"),
+        }
+    }
+
+    #[test]
+    fn seeks_and_buffers() {
+        let mut io = AllowStdIo::new(Cursor::new(b"hello world"));
+        let mut ctx = Context::from_waker(Waker::noop());
+        let res = Pin::new(&mut io).poll_seek(&mut ctx, SeekFrom::Start(6));
+        assert!(matches!(res, Poll::Ready(Ok(6))));
+
+        match Pin::new(&mut io).poll_fill_buf(&mut ctx) {
+            Poll::Ready(Ok(buf)) => assert_eq!(buf, b"world"),
+            res => panic!("unexpected {res:?}"),
+        }
+        Pin::new(&mut io).consume(5);
+        assert_eq!(io.into_inner().position(), 11);
+    }
+}
@@ -0,0 +1,139 @@
+use crate::{AsyncRead, AsyncSeek, AsyncWrite, IoSlice, IoSliceMut, Result, SeekFrom};
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+
+/// Wraps an asynchronous I/O type so that it can be used through the
+/// blocking `std::io` traits.
+///
+/// `BlockOn<T>` implements [`Read`](io::Read), [`Write`](io::Write) and
+/// [`Seek`](io::Seek) whenever `T` implements the corresponding
+/// asynchronous trait, by parking the current thread until each operation
+/// completes. For [`BufRead`](io::BufRead), wrap it in a
+/// [`std::io::BufReader`].
+///
+/// This is meant for handing asynchronous streams to APIs that only take
+/// `std::io` types. Calling it from a task deadlocks if the stream is waiting
+/// for something that only that task or its executor can do.
+#[derive(Debug, Default)]
+pub struct BlockOn<T> {
+    inner: T,
+}
+
+struct Unpark(Thread);
+
+impl Wake for Unpark {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+std::thread_local! {
+    static WAKER: Waker = Waker::from(Arc::new(Unpark(thread::current())));
+}
+
+impl<T> BlockOn<T> {
+    /// Wraps `inner`.
+    pub fn new(inner: T) -> Self {
+        Self { inner }
+    }
+
+    /// Returns a reference to the wrapped value.
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the wrapped value.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Consumes the wrapper, returning the wrapped value.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T: Unpin> BlockOn<T> {
+    /// Polls `f` with the wrapped value until it is ready, parking the
+    /// thread in between.
+    fn block<R>(&mut self, mut f: impl FnMut(Pin<&mut T>, &mut Context<'_>) -> Poll<R>) -> R {
+        WAKER.with(|waker| {
+            let mut ctx = Context::from_waker(waker);
+            loop {
+                if let Poll::Ready(res) = f(Pin::new(&mut self.inner), &mut ctx) {
+                    return res;
+                }
+                thread::park();
+            }
+        })
+    }
+}
+
+impl<T: AsyncRead + Unpin> io::Read for BlockOn<T> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        self.block(|inner, ctx| inner.poll_read(ctx, buf))
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> Result<usize> {
+        self.block(|inner, ctx| inner.poll_read_vectored(ctx, bufs))
+    }
+}
+
+impl<T: AsyncWrite + Unpin> io::Write for BlockOn<T> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.block(|inner, ctx| inner.poll_write(ctx, buf))
+    }
+
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> Result<usize> {
+        self.block(|inner, ctx| inner.poll_write_vectored(ctx, bufs))
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.block(|inner, ctx| inner.poll_flush(ctx))
+    }
+}
+
+impl<T: AsyncSeek + Unpin> io::Seek for BlockOn<T> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        self.block(|inner, ctx| inner.poll_seek(ctx, pos))
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::BlockOn;
+    use crate::pipe;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::thread;
+
+    #[test]
+    fn blocks_on_a_pipe() {
+        let (reader, writer) = pipe().unwrap();
+        let mut writer = BlockOn::new(writer);
+        let mut reader = BufReader::new(BlockOn::new(reader));
+
+        let handle = thread::spawn(move || {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            let mut rest = Vec::new();
+            reader.read_to_end(&mut rest).unwrap();
+            (line, rest)
+        });
+        writer.write_all(b"first line\n").unwrap();
+        let big = vec![7; 1 << 20];
+        writer.write_all(&big).unwrap();
+        writer.flush().unwrap();
+        drop(writer);
+
+        let (line, rest) = handle.join().unwrap();
+        assert_eq!(line, "first line\n");
+        assert!(rest == big);
+    }
+}
@@ -1,4 +1,24 @@
+//! Asynchronous I/O
+//!
+//! This crate contains the `AsyncRead`, `AsyncWrite`, `AsyncSeek`, and
+//! `AsyncBufRead` traits, the asynchronous analogs to
+//! `std::io::{Read, Write, Seek, BufRead}`.
+//!
+//! On Linux, the [`net`] module provides TCP and Unix domain sockets, and
+//! [`pipe`] anonymous pipes, driven by an `epoll` reactor and implementing
+//! these traits.
+//!
+//! [`AllowStdIo`] lets blocking `std::io` types be used where these traits
+//! are expected, and [`BlockOn`] goes the other way, for APIs that only take
+//! `std::io` types.
+//!
+//! All items of this library are only available when the `std` feature of this
+//! library is activated, and it is activated by default.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
 #[cfg(feature = "std")]
+#[macro_use]
 mod if_std {
     use std::io;
     use std::ops::DerefMut;
@@ -71,6 +91,32 @@ mod if_std {
         fn consume(self: Pin<&mut Self>, amt: usize);
     }
 
+    /// Runs a std I/O operation, retrying it when it was interrupted by a
+    /// signal.
+    pub(crate) fn retry<T>(mut f: impl FnMut() -> Result<T>) -> Result<T> {
+        loop {
+            match f() {
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                res => return res,
+            }
+        }
+    }
+
+    /// Like [`retry`], for `io::BufRead::fill_buf`, whose result borrows
+    /// the reader.
+    pub(crate) fn retry_fill_buf<R: io::BufRead + ?Sized>(reader: &mut R) -> Result<&[u8]> {
+        let reader: *mut R = reader;
+        loop {
+            // SAFETY: `reader` comes from a `&mut R` that lives as long as
+            // the result. Each borrow either ends with its iteration or is
+            // returned, so no two of them overlap.
+            match unsafe { &mut *reader }.fill_buf() {
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                res => return res,
+            }
+        }
+    }
+
     macro_rules! deref_async_read {
         () => {
             fn poll_read(
@@ -128,7 +174,7 @@ mod if_std {
                 _: &mut Context<'_>,
                 buf: &mut [u8],
             ) -> Poll<Result<usize>> {
-                Poll::Ready(io::Read::read(&mut *self, buf))
+                Poll::Ready(retry(|| io::Read::read(&mut *self, buf)))
             }
 
             fn poll_read_vectored(
@@ -136,7 +182,7 @@ mod if_std {
                 _: &mut Context<'_>,
                 bufs: &mut [IoSliceMut<'_>],
             ) -> Poll<Result<usize>> {
-                Poll::Ready(io::Read::read_vectored(&mut *self, bufs))
+                Poll::Ready(retry(|| io::Read::read_vectored(&mut *self, bufs)))
             }
         };
     }
@@ -218,7 +264,7 @@ mod if_std {
                 _: &mut Context<'_>,
                 buf: &[u8],
             ) -> Poll<Result<usize>> {
-                Poll::Ready(io::Write::write(&mut *self, buf))
+                Poll::Ready(retry(|| io::Write::write(&mut *self, buf)))
             }
 
             fn poll_write_vectored(
@@ -226,11 +272,11 @@ mod if_std {
                 _: &mut Context<'_>,
                 bufs: &[IoSlice<'_>],
             ) -> Poll<Result<usize>> {
-                Poll::Ready(io::Write::write_vectored(&mut *self, bufs))
+                Poll::Ready(retry(|| io::Write::write_vectored(&mut *self, bufs)))
             }
 
             fn poll_flush(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<()>> {
-                Poll::Ready(io::Write::flush(&mut *self))
+                Poll::Ready(retry(|| io::Write::flush(&mut *self)))
             }
 
             fn poll_close(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Result<()>> {
@@ -243,6 +289,18 @@ mod if_std {
         delegate_async_write_to_stdio!();
     }
 
+    macro_rules! delegate_async_seek_to_stdio {
+        () => {
+            fn poll_seek(
+                mut self: Pin<&mut Self>,
+                _: &mut Context<'_>,
+                pos: SeekFrom,
+            ) -> Poll<Result<u64>> {
+                Poll::Ready(retry(|| io::Seek::seek(&mut *self, pos)))
+            }
+        };
+    }
+
     macro_rules! deref_async_seek {
         () => {
             fn poll_seek(
@@ -314,7 +372,7 @@ mod if_std {
     macro_rules! delegate_async_buf_read_to_stdio {
         () => {
             fn poll_fill_buf(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<&[u8]>> {
-                Poll::Ready(io::BufRead::fill_buf(self.get_mut()))
+                Poll::Ready(retry_fill_buf(self.get_mut()))
             }
 
             fn consume(self: Pin<&mut Self>, amt: usize) {
@@ -330,3 +388,24 @@ mod if_std {
 
 #[cfg(feature = "std")]
 pub use self::if_std::*;
+
+#[cfg(feature = "std")]
+mod allow_std;
+#[cfg(feature = "std")]
+pub use self::allow_std::AllowStdIo;
+
+#[cfg(feature = "std")]
+mod block_on;
+#[cfg(feature = "std")]
+pub use self::block_on::BlockOn;
+
+#[cfg(all(feature = "std", target_os = "linux"))]
+mod reactor;
+
+#[cfg(all(feature = "std", target_os = "linux"))]
+pub mod net;
+
+#[cfg(all(feature = "std", target_os = "linux"))]
+mod pipe;
+#[cfg(all(feature = "std", target_os = "linux"))]
+pub use self::pipe::{pipe, PipeReader, PipeWriter};
@@ -0,0 +1,43 @@
+//! Asynchronous networking primitives, driven by the crate's epoll reactor.
+//!
+//! The first use of any of these types starts the reactor's background
+//! thread. They work with any executor.
+
+mod tcp;
+pub use self::tcp::{TcpListener, TcpStream};
+
+mod unix;
+pub use self::unix::{UnixDatagram, UnixListener, UnixStream};
+
+mod addr;
+mod socket;
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use crate::{AsyncRead, AsyncWrite};
+    use std::future::poll_fn;
+    use std::io;
+    use std::pin::Pin;
+
+    pub(crate) async fn write_all<W: AsyncWrite + Unpin>(
+        w: &mut W,
+        mut buf: &[u8],
+    ) -> io::Result<()> {
+        while !buf.is_empty() {
+            let n = poll_fn(|ctx| Pin::new(&mut *w).poll_write(ctx, buf)).await?;
+            buf = &buf[n..];
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn read_to_end<R: AsyncRead + Unpin>(r: &mut R) -> io::Result<Vec<u8>> {
+        let mut out = Vec::new();
+        let mut buf = [0; 1024];
+        loop {
+            match poll_fn(|ctx| Pin::new(&mut *r).poll_read(ctx, &mut buf)).await? {
+                0 => return Ok(out),
+                n => out.extend_from_slice(&buf[..n]),
+            }
+        }
+    }
+}
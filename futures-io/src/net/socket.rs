@@ -0,0 +1,41 @@
+use std::io;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+
+/// Creates a non-blocking stream socket and starts connecting it to the C
+/// socket address `addr`.
+///
+/// The connection is established once the socket becomes writable, after
+/// which `SO_ERROR` tells whether it failed.
+pub(crate) fn start_connect<A>(
+    domain: libc::c_int,
+    addr: &A,
+    len: libc::socklen_t,
+) -> io::Result<OwnedFd> {
+    let fd = unsafe {
+        libc::socket(
+            domain,
+            libc::SOCK_STREAM | libc::SOCK_NONBLOCK | libc::SOCK_CLOEXEC,
+            0,
+        )
+    };
+    if fd == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    // SAFETY: `socket` returned a fresh descriptor.
+    let socket = unsafe { OwnedFd::from_raw_fd(fd) };
+
+    let ret = unsafe {
+        libc::connect(
+            socket.as_raw_fd(),
+            addr as *const A as *const libc::sockaddr,
+            len,
+        )
+    };
+    if ret == -1 {
+        let err = io::Error::last_os_error();
+        if err.raw_os_error() != Some(libc::EINPROGRESS) {
+            return Err(err);
+        }
+    }
+    Ok(socket)
+}
@@ -0,0 +1,328 @@
+use super::addr::socket_addr;
+use super::socket::start_connect;
+use crate::reactor::{Direction, Evented};
+use crate::{AsyncRead, AsyncWrite, IoSlice, IoSliceMut};
+use std::future::poll_fn;
+use std::io::{self, Read, Write};
+use std::net::{self, Shutdown, SocketAddr};
+use std::os::fd::{AsRawFd, RawFd};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// A TCP socket server, listening for connections.
+///
+/// Accepted connections are returned as [`TcpStream`]s.
+#[derive(Debug)]
+pub struct TcpListener {
+    inner: Evented<net::TcpListener>,
+}
+
+impl TcpListener {
+    /// Creates a listener bound to `addr`.
+    ///
+    /// Binding to port 0 picks a free port; see
+    /// [`local_addr`](TcpListener::local_addr).
+    pub fn bind(addr: SocketAddr) -> io::Result<Self> {
+        Self::from_std(net::TcpListener::bind(addr)?)
+    }
+
+    /// Converts a standard library listener, switching it to non-blocking
+    /// mode.
+    pub fn from_std(listener: net::TcpListener) -> io::Result<Self> {
+        listener.set_nonblocking(true)?;
+        Ok(Self {
+            inner: Evented::new(listener)?,
+        })
+    }
+
+    /// Returns the local address this listener is bound to.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.inner.get_ref().local_addr()
+    }
+
+    /// Accepts a new incoming connection.
+    pub async fn accept(&self) -> io::Result<(TcpStream, SocketAddr)> {
+        poll_fn(|ctx| self.poll_accept(ctx)).await
+    }
+
+    /// Polls to accept a new incoming connection.
+    pub fn poll_accept(&self, ctx: &mut Context<'_>) -> Poll<io::Result<(TcpStream, SocketAddr)>> {
+        match self
+            .inner
+            .poll_io(ctx, Direction::Read, |listener| listener.accept())
+        {
+            Poll::Ready(Ok((stream, addr))) => {
+                Poll::Ready(TcpStream::from_std(stream).map(|stream| (stream, addr)))
+            }
+            Poll::Ready(Err(e)) => Poll::Ready(Err(e)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl AsRawFd for TcpListener {
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.get_ref().as_raw_fd()
+    }
+}
+
+/// A TCP stream between a local and a remote socket.
+///
+/// Reading and writing can happen from separate tasks through `&TcpStream`,
+/// which implements [`AsyncRead`] and [`AsyncWrite`] as well. Only one task
+/// at a time should wait on each direction.
+#[derive(Debug)]
+pub struct TcpStream {
+    inner: Evented<net::TcpStream>,
+}
+
+impl TcpStream {
+    /// Opens a TCP connection to `addr`.
+    pub async fn connect(addr: SocketAddr) -> io::Result<Self> {
+        let domain = match addr {
+            SocketAddr::V4(_) => libc::AF_INET,
+            SocketAddr::V6(_) => libc::AF_INET6,
+        };
+        let (storage, len) = socket_addr(&addr);
+        let socket = start_connect(domain, &storage, len)?;
+
+        let stream = Self {
+            inner: Evented::new(net::TcpStream::from(socket))?,
+        };
+        // The socket becomes writable once the connection is established or
+        // failed; `SO_ERROR` tells which.
+        poll_fn(|ctx| stream.inner.poll_ready(ctx, Direction::Write)).await;
+        match stream.inner.get_ref().take_error()? {
+            Some(err) => Err(err),
+            None => Ok(stream),
+        }
+    }
+
+    /// Converts a standard library stream, switching it to non-blocking
+    /// mode.
+    pub fn from_std(stream: net::TcpStream) -> io::Result<Self> {
+        stream.set_nonblocking(true)?;
+        Ok(Self {
+            inner: Evented::new(stream)?,
+        })
+    }
+
+    /// Returns the local address of this connection.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.inner.get_ref().local_addr()
+    }
+
+    /// Returns the remote address of this connection.
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        self.inner.get_ref().peer_addr()
+    }
+
+    /// Shuts down the read half, the write half, or both halves of this
+    /// connection.
+    pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
+        self.inner.get_ref().shutdown(how)
+    }
+
+    /// Gets the value of the `TCP_NODELAY` option on this socket.
+    pub fn nodelay(&self) -> io::Result<bool> {
+        self.inner.get_ref().nodelay()
+    }
+
+    /// Sets the value of the `TCP_NODELAY` option on this socket.
+    pub fn set_nodelay(&self, nodelay: bool) -> io::Result<()> {
+        self.inner.get_ref().set_nodelay(nodelay)
+    }
+}
+
+impl AsRawFd for TcpStream {
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.get_ref().as_raw_fd()
+    }
+}
+
+impl AsyncRead for &TcpStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        ctx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        self.inner
+            .poll_io(ctx, Direction::Read, |mut s| s.read(buf))
+    }
+
+    fn poll_read_vectored(
+        self: Pin<&mut Self>,
+        ctx: &mut Context<'_>,
+        bufs: &mut [IoSliceMut<'_>],
+    ) -> Poll<io::Result<usize>> {
+        self.inner
+            .poll_io(ctx, Direction::Read, |mut s| s.read_vectored(bufs))
+    }
+}
+
+impl AsyncWrite for &TcpStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        ctx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.inner
+            .poll_io(ctx, Direction::Write, |mut s| s.write(buf))
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        ctx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        self.inner
+            .poll_io(ctx, Direction::Write, |mut s| s.write_vectored(bufs))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    /// Shuts down the write half of the connection.
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(self.shutdown(Shutdown::Write))
+    }
+}
+
+impl AsyncRead for TcpStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        ctx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut &*self).poll_read(ctx, buf)
+    }
+
+    fn poll_read_vectored(
+        self: Pin<&mut Self>,
+        ctx: &mut Context<'_>,
+        bufs: &mut [IoSliceMut<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut &*self).poll_read_vectored(ctx, bufs)
+    }
+}
+
+impl AsyncWrite for TcpStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        ctx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut &*self).poll_write(ctx, buf)
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        ctx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut &*self).poll_write_vectored(ctx, bufs)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut &*self).poll_flush(ctx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut &*self).poll_close(ctx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TcpListener, TcpStream};
+    use crate::net::tests::{read_to_end, write_all};
+    use crate::{AsyncRead, AsyncWrite, IoSlice, IoSliceMut};
+    use futures_executor::block_on;
+    use std::future::poll_fn;
+    use std::io;
+    use std::net::SocketAddr;
+    use std::pin::Pin;
+    use std::thread;
+
+    fn loopback() -> SocketAddr {
+        "127.0.0.1:0".parse().unwrap()
+    }
+
+    #[test]
+    fn echo_over_loopback() {
+        let listener = TcpListener::bind(loopback()).unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let server = thread::spawn(move || {
+            block_on(async {
+                let (mut stream, peer) = listener.accept().await.unwrap();
+                assert_eq!(peer, stream.peer_addr().unwrap());
+                let data = read_to_end(&mut stream).await.unwrap();
+                write_all(&mut stream, &data).await.unwrap();
+                poll_fn(|ctx| Pin::new(&mut stream).poll_close(ctx))
+                    .await
+                    .unwrap();
+            })
+        });
+
+        let mut stream = block_on(TcpStream::connect(addr)).unwrap();
+        assert_eq!(stream.peer_addr().unwrap(), addr);
+        // Large enough to fill the socket buffers and block both sides.
+        let payload: Vec<u8> = (0..4 << 20).map(|i| i as u8).collect();
+        let (mut reader, mut writer) = (&stream, &stream);
+        let echoed = thread::scope(|s| {
+            s.spawn(|| {
+                block_on(async {
+                    write_all(&mut writer, &payload).await.unwrap();
+                    poll_fn(|ctx| Pin::new(&mut writer).poll_close(ctx))
+                        .await
+                        .unwrap();
+                })
+            });
+            block_on(read_to_end(&mut reader)).unwrap()
+        });
+        assert!(echoed == payload);
+        assert_eq!(block_on(read_to_end(&mut stream)).unwrap(), b"");
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn vectored_io() {
+        let listener = TcpListener::bind(loopback()).unwrap();
+        let addr = listener.local_addr().unwrap();
+        block_on(async {
+            let mut client = TcpStream::connect(addr).await.unwrap();
+            let (mut server, _) = listener.accept().await.unwrap();
+
+            let bufs = [IoSlice::new(b"hello "), IoSlice::new(b"world")];
+            let n = poll_fn(|ctx| Pin::new(&mut client).poll_write_vectored(ctx, &bufs))
+                .await
+                .unwrap();
+            assert_eq!(n, 11);
+
+            let (mut a, mut b) = ([0; 5], [0; 6]);
+            let mut read = 0;
+            while read < 11 {
+                let (a_rest, b_rest) = if read < 5 {
+                    (&mut a[read..], &mut b[..])
+                } else {
+                    (&mut a[5..], &mut b[read - 5..])
+                };
+                let mut bufs = [IoSliceMut::new(a_rest), IoSliceMut::new(b_rest)];
+                read += poll_fn(|ctx| Pin::new(&mut server).poll_read_vectored(ctx, &mut bufs))
+                    .await
+                    .unwrap();
+            }
+            assert_eq!(&a, b"hello");
+            assert_eq!(&b, b" world");
+        });
+    }
+
+    #[test]
+    fn connect_refused() {
+        let addr = TcpListener::bind(loopback()).unwrap().local_addr().unwrap();
+        let err = block_on(TcpStream::connect(addr)).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::ConnectionRefused);
+    }
+}
@@ -0,0 +1,581 @@
+use super::addr::unix_addr;
+use super::socket::start_connect;
+use crate::reactor::{Direction, Evented};
+use crate::{AsyncRead, AsyncWrite, IoSlice, IoSliceMut};
+use std::future::poll_fn;
+use std::io::{self, Read, Write};
+use std::mem;
+use std::net::Shutdown;
+use std::os::fd::{AsRawFd, BorrowedFd, FromRawFd, OwnedFd, RawFd};
+use std::os::unix::net::{self, SocketAddr};
+use std::path::Path;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// The most descriptors Linux passes in a single message.
+const SCM_MAX_FD: usize = 253;
+
+/// A Unix domain socket server, listening for connections.
+#[derive(Debug)]
+pub struct UnixListener {
+    inner: Evented<net::UnixListener>,
+}
+
+impl UnixListener {
+    /// Creates a listener bound to the socket file at `path`.
+    ///
+    /// The file is not removed when the listener is dropped.
+    pub fn bind(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::from_std(net::UnixListener::bind(path)?)
+    }
+
+    /// Converts a standard library listener, switching it to non-blocking
+    /// mode.
+    pub fn from_std(listener: net::UnixListener) -> io::Result<Self> {
+        listener.set_nonblocking(true)?;
+        Ok(Self {
+            inner: Evented::new(listener)?,
+        })
+    }
+
+    /// Returns the local address this listener is bound to.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.inner.get_ref().local_addr()
+    }
+
+    /// Accepts a new incoming connection.
+    pub async fn accept(&self) -> io::Result<(UnixStream, SocketAddr)> {
+        poll_fn(|ctx| self.poll_accept(ctx)).await
+    }
+
+    /// Polls to accept a new incoming connection.
+    pub fn poll_accept(&self, ctx: &mut Context<'_>) -> Poll<io::Result<(UnixStream, SocketAddr)>> {
+        match self
+            .inner
+            .poll_io(ctx, Direction::Read, |listener| listener.accept())
+        {
+            Poll::Ready(Ok((stream, addr))) => {
+                Poll::Ready(UnixStream::from_std(stream).map(|stream| (stream, addr)))
+            }
+            Poll::Ready(Err(e)) => Poll::Ready(Err(e)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl AsRawFd for UnixListener {
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.get_ref().as_raw_fd()
+    }
+}
+
+/// A Unix domain stream socket.
+///
+/// Like [`TcpStream`](super::TcpStream), `&UnixStream` implements
+/// [`AsyncRead`] and [`AsyncWrite`] too. File descriptors can be passed to
+/// the peer alongside data with
+/// [`send_with_fds`](UnixStream::send_with_fds).
+#[derive(Debug)]
+pub struct UnixStream {
+    inner: Evented<net::UnixStream>,
+}
+
+impl UnixStream {
+    /// Connects to the socket file at `path`.
+    pub async fn connect(path: impl AsRef<Path>) -> io::Result<Self> {
+        let (addr, len) = unix_addr(path.as_ref())?;
+        let socket = start_connect(libc::AF_UNIX, &addr, len)?;
+
+        let stream = Self {
+            inner: Evented::new(net::UnixStream::from(socket))?,
+        };
+        poll_fn(|ctx| stream.inner.poll_ready(ctx, Direction::Write)).await;
+        match stream.inner.get_ref().take_error()? {
+            Some(err) => Err(err),
+            None => Ok(stream),
+        }
+    }
+
+    /// Creates a pair of connected sockets.
+    pub fn pair() -> io::Result<(Self, Self)> {
+        let (a, b) = net::UnixStream::pair()?;
+        Ok((Self::from_std(a)?, Self::from_std(b)?))
+    }
+
+    /// Converts a standard library stream, switching it to non-blocking
+    /// mode.
+    pub fn from_std(stream: net::UnixStream) -> io::Result<Self> {
+        stream.set_nonblocking(true)?;
+        Ok(Self {
+            inner: Evented::new(stream)?,
+        })
+    }
+
+    /// Returns the local address of this connection.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.inner.get_ref().local_addr()
+    }
+
+    /// Returns the remote address of this connection.
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        self.inner.get_ref().peer_addr()
+    }
+
+    /// Shuts down the read half, the write half, or both halves of this
+    /// connection.
+    pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
+        self.inner.get_ref().shutdown(how)
+    }
+
+    /// Sends data from `buf` along with the file descriptors `fds`.
+    ///
+    /// The descriptors are attached to the first byte sent, so they are only
+    /// passed if at least one byte is written. At most 253 descriptors can be
+    /// sent at once.
+    pub async fn send_with_fds(&self, buf: &[u8], fds: &[BorrowedFd<'_>]) -> io::Result<usize> {
+        poll_fn(|ctx| self.poll_send_with_fds(ctx, buf, fds)).await
+    }
+
+    /// Polls to send data from `buf` along with the file descriptors `fds`.
+    pub fn poll_send_with_fds(
+        &self,
+        ctx: &mut Context<'_>,
+        buf: &[u8],
+        fds: &[BorrowedFd<'_>],
+    ) -> Poll<io::Result<usize>> {
+        self.inner.poll_io(ctx, Direction::Write, |s| {
+            send_with_fds(s.as_raw_fd(), buf, fds)
+        })
+    }
+
+    /// Receives data into `buf`, appending any file descriptors that came
+    /// with it to `fds`.
+    ///
+    /// The received descriptors are close-on-exec.
+    pub async fn recv_with_fds(&self, buf: &mut [u8], fds: &mut Vec<OwnedFd>) -> io::Result<usize> {
+        poll_fn(|ctx| self.poll_recv_with_fds(ctx, buf, fds)).await
+    }
+
+    /// Polls to receive data into `buf`, appending any file descriptors that
+    /// came with it to `fds`.
+    pub fn poll_recv_with_fds(
+        &self,
+        ctx: &mut Context<'_>,
+        buf: &mut [u8],
+        fds: &mut Vec<OwnedFd>,
+    ) -> Poll<io::Result<usize>> {
+        self.inner.poll_io(ctx, Direction::Read, |s| {
+            recv_with_fds(s.as_raw_fd(), buf, fds)
+        })
+    }
+}
+
+impl AsRawFd for UnixStream {
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.get_ref().as_raw_fd()
+    }
+}
+
+/// A buffer for control messages carrying up to `SCM_MAX_FD` descriptors,
+/// aligned for `cmsghdr`.
+fn cmsg_buffer() -> Vec<u64> {
+    let len = unsafe { libc::CMSG_SPACE((SCM_MAX_FD * mem::size_of::<RawFd>()) as u32) };
+    vec![0; (len as usize).div_ceil(mem::size_of::<u64>())]
+}
+
+fn send_with_fds(socket: RawFd, buf: &[u8], fds: &[BorrowedFd<'_>]) -> io::Result<usize> {
+    if fds.len() > SCM_MAX_FD {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "too many file descriptors",
+        ));
+    }
+
+    let mut iov = libc::iovec {
+        iov_base: buf.as_ptr() as *mut libc::c_void,
+        iov_len: buf.len(),
+    };
+    // SAFETY: an all-zero `msghdr` is valid and describes no buffers.
+    let mut msg: libc::msghdr = unsafe { mem::zeroed() };
+    msg.msg_iov = &mut iov;
+    msg.msg_iovlen = 1;
+
+    let mut control = cmsg_buffer();
+    if !fds.is_empty() {
+        let data_len = (fds.len() * mem::size_of::<RawFd>()) as u32;
+        msg.msg_control = control.as_mut_ptr().cast();
+        msg.msg_controllen = unsafe { libc::CMSG_SPACE(data_len) } as _;
+        // SAFETY: the control buffer is large enough and suitably aligned
+        // for a header followed by `fds.len()` descriptors.
+        unsafe {
+            let cmsg = libc::CMSG_FIRSTHDR(&msg);
+            (*cmsg).cmsg_level = libc::SOL_SOCKET;
+            (*cmsg).cmsg_type = libc::SCM_RIGHTS;
+            (*cmsg).cmsg_len = libc::CMSG_LEN(data_len) as _;
+            let data = libc::CMSG_DATA(cmsg).cast::<RawFd>();
+            for (i, fd) in fds.iter().enumerate() {
+                data.add(i).write_unaligned(fd.as_raw_fd());
+            }
+        }
+    }
+
+    let n = unsafe { libc::sendmsg(socket, &msg, libc::MSG_NOSIGNAL) };
+    if n == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(n as usize)
+    }
+}
+
+fn recv_with_fds(socket: RawFd, buf: &mut [u8], fds: &mut Vec<OwnedFd>) -> io::Result<usize> {
+    let mut iov = libc::iovec {
+        iov_base: buf.as_mut_ptr().cast(),
+        iov_len: buf.len(),
+    };
+    let mut control = cmsg_buffer();
+    // SAFETY: an all-zero `msghdr` is valid and describes no buffers.
+    let mut msg: libc::msghdr = unsafe { mem::zeroed() };
+    msg.msg_iov = &mut iov;
+    msg.msg_iovlen = 1;
+    msg.msg_control = control.as_mut_ptr().cast();
+    msg.msg_controllen = (control.len() * mem::size_of::<u64>()) as _;
+
+    let n = unsafe { libc::recvmsg(socket, &mut msg, libc::MSG_CMSG_CLOEXEC) };
+    if n == -1 {
+        return Err(io::Error::last_os_error());
+    }
+
+    // SAFETY: the kernel filled in `msg_controllen` bytes of well-formed
+    // control messages, and each `SCM_RIGHTS` descriptor is now owned by
+    // this process.
+    unsafe {
+        let mut cmsg = libc::CMSG_FIRSTHDR(&msg);
+        while !cmsg.is_null() {
+            if (*cmsg).cmsg_level == libc::SOL_SOCKET && (*cmsg).cmsg_type == libc::SCM_RIGHTS {
+                let data = libc::CMSG_DATA(cmsg).cast::<RawFd>();
+                let data_len = (*cmsg).cmsg_len as usize - libc::CMSG_LEN(0) as usize;
+                for i in 0..data_len / mem::size_of::<RawFd>() {
+                    fds.push(OwnedFd::from_raw_fd(data.add(i).read_unaligned()));
+                }
+            }
+            cmsg = libc::CMSG_NXTHDR(&msg, cmsg);
+        }
+    }
+    Ok(n as usize)
+}
+
+impl AsyncRead for &UnixStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        ctx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        self.inner
+            .poll_io(ctx, Direction::Read, |mut s| s.read(buf))
+    }
+
+    fn poll_read_vectored(
+        self: Pin<&mut Self>,
+        ctx: &mut Context<'_>,
+        bufs: &mut [IoSliceMut<'_>],
+    ) -> Poll<io::Result<usize>> {
+        self.inner
+            .poll_io(ctx, Direction::Read, |mut s| s.read_vectored(bufs))
+    }
+}
+
+impl AsyncWrite for &UnixStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        ctx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.inner
+            .poll_io(ctx, Direction::Write, |mut s| s.write(buf))
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        ctx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        self.inner
+            .poll_io(ctx, Direction::Write, |mut s| s.write_vectored(bufs))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    /// Shuts down the write half of the connection.
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(self.shutdown(Shutdown::Write))
+    }
+}
+
+impl AsyncRead for UnixStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        ctx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut &*self).poll_read(ctx, buf)
+    }
+
+    fn poll_read_vectored(
+        self: Pin<&mut Self>,
+        ctx: &mut Context<'_>,
+        bufs: &mut [IoSliceMut<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut &*self).poll_read_vectored(ctx, bufs)
+    }
+}
+
+impl AsyncWrite for UnixStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        ctx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut &*self).poll_write(ctx, buf)
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        ctx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut &*self).poll_write_vectored(ctx, bufs)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut &*self).poll_flush(ctx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut &*self).poll_close(ctx)
+    }
+}
+
+/// A Unix domain datagram socket.
+#[derive(Debug)]
+pub struct UnixDatagram {
+    inner: Evented<net::UnixDatagram>,
+}
+
+impl UnixDatagram {
+    /// Creates a socket bound to the socket file at `path`.
+    pub fn bind(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::from_std(net::UnixDatagram::bind(path)?)
+    }
+
+    /// Creates a socket that is not bound to any address.
+    pub fn unbound() -> io::Result<Self> {
+        Self::from_std(net::UnixDatagram::unbound()?)
+    }
+
+    /// Creates a pair of connected sockets.
+    pub fn pair() -> io::Result<(Self, Self)> {
+        let (a, b) = net::UnixDatagram::pair()?;
+        Ok((Self::from_std(a)?, Self::from_std(b)?))
+    }
+
+    /// Converts a standard library socket, switching it to non-blocking
+    /// mode.
+    pub fn from_std(socket: net::UnixDatagram) -> io::Result<Self> {
+        socket.set_nonblocking(true)?;
+        Ok(Self {
+            inner: Evented::new(socket)?,
+        })
+    }
+
+    /// Connects the socket to the socket file at `path`, so that
+    /// [`send`](UnixDatagram::send) and [`recv`](UnixDatagram::recv) can be
+    /// used.
+    pub fn connect(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        self.inner.get_ref().connect(path)
+    }
+
+    /// Returns the local address of this socket.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.inner.get_ref().local_addr()
+    }
+
+    /// Returns the address of the peer this socket is connected to.
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        self.inner.get_ref().peer_addr()
+    }
+
+    /// Shuts down the read half, the write half, or both halves of this
+    /// socket.
+    pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
+        self.inner.get_ref().shutdown(how)
+    }
+
+    /// Sends a datagram to the connected peer.
+    pub async fn send(&self, buf: &[u8]) -> io::Result<usize> {
+        poll_fn(|ctx| self.poll_send(ctx, buf)).await
+    }
+
+    /// Polls to send a datagram to the connected peer.
+    pub fn poll_send(&self, ctx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        self.inner.poll_io(ctx, Direction::Write, |s| s.send(buf))
+    }
+
+    /// Receives a datagram from the connected peer.
+    ///
+    /// Bytes that don't fit in `buf` are discarded.
+    pub async fn recv(&self, buf: &mut [u8]) -> io::Result<usize> {
+        poll_fn(|ctx| self.poll_recv(ctx, buf)).await
+    }
+
+    /// Polls to receive a datagram from the connected peer.
+    pub fn poll_recv(&self, ctx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        self.inner.poll_io(ctx, Direction::Read, |s| s.recv(buf))
+    }
+
+    /// Sends a datagram to the socket file at `path`.
+    pub async fn send_to(&self, buf: &[u8], path: impl AsRef<Path>) -> io::Result<usize> {
+        let path = path.as_ref();
+        poll_fn(|ctx| self.poll_send_to(ctx, buf, path)).await
+    }
+
+    /// Polls to send a datagram to the socket file at `path`.
+    pub fn poll_send_to(
+        &self,
+        ctx: &mut Context<'_>,
+        buf: &[u8],
+        path: &Path,
+    ) -> Poll<io::Result<usize>> {
+        self.inner
+            .poll_io(ctx, Direction::Write, |s| s.send_to(buf, path))
+    }
+
+    /// Receives a datagram, returning its length and sender.
+    pub async fn recv_from(&self, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)> {
+        poll_fn(|ctx| self.poll_recv_from(ctx, buf)).await
+    }
+
+    /// Polls to receive a datagram, returning its length and sender.
+    pub fn poll_recv_from(
+        &self,
+        ctx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<(usize, SocketAddr)>> {
+        self.inner
+            .poll_io(ctx, Direction::Read, |s| s.recv_from(buf))
+    }
+}
+
+impl AsRawFd for UnixDatagram {
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.get_ref().as_raw_fd()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{UnixDatagram, UnixListener, UnixStream};
+    use crate::net::tests::{read_to_end, write_all};
+    use crate::{AsyncWrite, PipeReader};
+    use futures_executor::block_on;
+    use std::future::poll_fn;
+    use std::io;
+    use std::os::fd::AsFd;
+    use std::path::PathBuf;
+    use std::pin::Pin;
+    use std::{env, fs, process, thread};
+
+    fn socket_path(name: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("futures-io-{}-{}.sock", name, process::id()));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn stream_over_socket_file() {
+        let path = socket_path("stream");
+        let listener = UnixListener::bind(&path).unwrap();
+        assert_eq!(listener.local_addr().unwrap().as_pathname(), Some(&*path));
+
+        let server = thread::spawn(move || {
+            block_on(async {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let data = read_to_end(&mut stream).await.unwrap();
+                write_all(&mut stream, &data).await.unwrap();
+            })
+        });
+
+        block_on(async {
+            let mut stream = UnixStream::connect(&path).await.unwrap();
+            let payload = vec![7; 1 << 20];
+            write_all(&mut stream, &payload).await.unwrap();
+            poll_fn(|ctx| Pin::new(&mut stream).poll_close(ctx))
+                .await
+                .unwrap();
+            assert!(read_to_end(&mut stream).await.unwrap() == payload);
+        });
+        server.join().unwrap();
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn connect_to_missing_socket_fails() {
+        let path = socket_path("missing");
+        let err = block_on(UnixStream::connect(&path)).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn passes_file_descriptors() {
+        let (a, b) = UnixStream::pair().unwrap();
+        let (reader, writer) = io::pipe().unwrap();
+        let mut reader = PipeReader::from_std(reader).unwrap();
+
+        block_on(async {
+            let n = a.send_with_fds(b"x", &[writer.as_fd()]).await.unwrap();
+            assert_eq!(n, 1);
+            drop(writer);
+
+            let mut buf = [0; 8];
+            let mut fds = Vec::new();
+            let n = b.recv_with_fds(&mut buf, &mut fds).await.unwrap();
+            assert_eq!(&buf[..n], b"x");
+            assert_eq!(fds.len(), 1);
+
+            // The received descriptor is the write end of the pipe.
+            let mut writer = fs::File::from(fds.pop().unwrap());
+            io::Write::write_all(&mut writer, b"through the pipe").unwrap();
+            drop(writer);
+            assert_eq!(read_to_end(&mut reader).await.unwrap(), b"through the pipe");
+        });
+    }
+
+    #[test]
+    fn datagrams() {
+        let path = socket_path("dgram");
+        let server = UnixDatagram::bind(&path).unwrap();
+        let client = UnixDatagram::unbound().unwrap();
+
+        let mut buf = [0; 16];
+        let recv = thread::scope(|s| {
+            let recv = s.spawn(|| block_on(server.recv_from(&mut buf)));
+            block_on(client.send_to(b"ping", &path)).unwrap();
+            recv.join().unwrap()
+        });
+        let (n, from) = recv.unwrap();
+        assert_eq!(&buf[..n], b"ping");
+        assert!(from.is_unnamed());
+
+        block_on(async {
+            let (a, b) = UnixDatagram::pair().unwrap();
+            a.send(b"one").await.unwrap();
+            a.send(b"two").await.unwrap();
+            let n = b.recv(&mut buf).await.unwrap();
+            assert_eq!(&buf[..n], b"one");
+            let n = b.recv(&mut buf).await.unwrap();
+            assert_eq!(&buf[..n], b"two");
+        });
+        fs::remove_file(&path).unwrap();
+    }
+}
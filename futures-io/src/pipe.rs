@@ -0,0 +1,191 @@
+//! Anonymous pipes, driven by the crate's epoll reactor.
+
+use crate::reactor::{Direction, Evented};
+use crate::{AsyncRead, AsyncWrite, IoSlice, IoSliceMut};
+use std::io::{self, Read, Write};
+use std::os::fd::{AsFd, AsRawFd, BorrowedFd, RawFd};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Creates an anonymous pipe, returning its read and write ends.
+///
+/// Both ends are close-on-exec.
+pub fn pipe() -> io::Result<(PipeReader, PipeWriter)> {
+    let (reader, writer) = io::pipe()?;
+    Ok((PipeReader::from_std(reader)?, PipeWriter::from_std(writer)?))
+}
+
+fn set_nonblocking(fd: BorrowedFd<'_>) -> io::Result<()> {
+    let fd = fd.as_raw_fd();
+    let flags = unsafe { libc::fcntl(fd, libc::F_GETFL) };
+    if flags == -1 || unsafe { libc::fcntl(fd, libc::F_SETFL, flags | libc::O_NONBLOCK) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// The read end of a [`pipe`].
+///
+/// Reads return end-of-file once every write end is closed.
+#[derive(Debug)]
+pub struct PipeReader {
+    inner: Evented<io::PipeReader>,
+}
+
+impl PipeReader {
+    /// Converts the read end of a standard library pipe, switching it to
+    /// non-blocking mode.
+    pub fn from_std(reader: io::PipeReader) -> io::Result<Self> {
+        set_nonblocking(reader.as_fd())?;
+        Ok(Self {
+            inner: Evented::new(reader)?,
+        })
+    }
+}
+
+impl AsRawFd for PipeReader {
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.get_ref().as_raw_fd()
+    }
+}
+
+impl AsFd for PipeReader {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.inner.get_ref().as_fd()
+    }
+}
+
+impl AsyncRead for PipeReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        ctx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        self.inner
+            .poll_io(ctx, Direction::Read, |mut p| p.read(buf))
+    }
+
+    fn poll_read_vectored(
+        self: Pin<&mut Self>,
+        ctx: &mut Context<'_>,
+        bufs: &mut [IoSliceMut<'_>],
+    ) -> Poll<io::Result<usize>> {
+        self.inner
+            .poll_io(ctx, Direction::Read, |mut p| p.read_vectored(bufs))
+    }
+}
+
+/// The write end of a [`pipe`].
+///
+/// [`poll_close`](AsyncWrite::poll_close) closes the descriptor, so that the
+/// reader sees end-of-file without waiting for the writer to be dropped.
+#[derive(Debug)]
+pub struct PipeWriter {
+    inner: Option<Evented<io::PipeWriter>>,
+}
+
+impl PipeWriter {
+    /// Converts the write end of a standard library pipe, switching it to
+    /// non-blocking mode.
+    pub fn from_std(writer: io::PipeWriter) -> io::Result<Self> {
+        set_nonblocking(writer.as_fd())?;
+        Ok(Self {
+            inner: Some(Evented::new(writer)?),
+        })
+    }
+
+    fn inner(&self) -> io::Result<&Evented<io::PipeWriter>> {
+        self.inner
+            .as_ref()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::BrokenPipe, "pipe writer was closed"))
+    }
+}
+
+impl AsyncWrite for PipeWriter {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        ctx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.inner()?
+            .poll_io(ctx, Direction::Write, |mut p| p.write(buf))
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        ctx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        self.inner()?
+            .poll_io(ctx, Direction::Write, |mut p| p.write_vectored(bufs))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.get_mut().inner = None;
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::pipe;
+    use crate::{AsyncRead, AsyncWrite, IoSlice};
+    use futures_executor::block_on;
+    use std::future::poll_fn;
+    use std::io;
+    use std::pin::Pin;
+    use std::thread;
+
+    #[test]
+    fn reader_sees_eof_after_close() {
+        let (mut reader, mut writer) = pipe().unwrap();
+        // More than the default pipe capacity, so the writer has to wait.
+        let payload: Vec<u8> = (0..1 << 18).map(|i| i as u8).collect();
+
+        let received = thread::scope(|s| {
+            s.spawn(|| {
+                block_on(async {
+                    let mut buf = &payload[..];
+                    while !buf.is_empty() {
+                        let bufs = [
+                            IoSlice::new(&buf[..buf.len() / 2]),
+                            IoSlice::new(&buf[buf.len() / 2..]),
+                        ];
+                        let n =
+                            poll_fn(|ctx| Pin::new(&mut writer).poll_write_vectored(ctx, &bufs))
+                                .await
+                                .unwrap();
+                        buf = &buf[n..];
+                    }
+                    poll_fn(|ctx| Pin::new(&mut writer).poll_close(ctx))
+                        .await
+                        .unwrap();
+                })
+            });
+            block_on(async {
+                let mut out = Vec::new();
+                let mut buf = [0; 4096];
+                loop {
+                    match poll_fn(|ctx| Pin::new(&mut reader).poll_read(ctx, &mut buf))
+                        .await
+                        .unwrap()
+                    {
+                        0 => return out,
+                        n => out.extend_from_slice(&buf[..n]),
+                    }
+                }
+            })
+        });
+        assert!(received == payload);
+
+        let err = block_on(poll_fn(|ctx| {
+            Pin::new(&mut writer).poll_write(ctx, b"late")
+        }))
+        .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::BrokenPipe);
+    }
+}
@@ -0,0 +1,270 @@
+//! A readiness reactor on top of Linux `epoll`.
+//!
+//! File descriptors are registered edge-triggered for both directions, and a
+//! background thread waits on the epoll instance. For every event it marks
+//! the affected directions ready and wakes the task waiting on each of them.
+//! Tasks retry their I/O while a direction is ready, and clear the readiness
+//! again once the operation would block.
+
+use std::collections::HashMap;
+use std::io;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+
+/// The two readiness interests of a registered file descriptor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Direction {
+    Read,
+    Write,
+}
+
+/// The global reactor, started on first use.
+struct Reactor {
+    epoll: OwnedFd,
+    sources: Mutex<HashMap<u64, Arc<ScheduledIo>>>,
+    next_token: AtomicU64,
+}
+
+/// The readiness of one direction of a registered file descriptor.
+#[derive(Debug, Default)]
+struct Readiness {
+    ready: bool,
+    /// Bumped on every event, so that a stale `clear_ready` can't discard
+    /// readiness that arrived after the operation that would block.
+    tick: u64,
+    waker: Option<Waker>,
+}
+
+#[derive(Debug, Default)]
+struct ScheduledIo {
+    read: Mutex<Readiness>,
+    write: Mutex<Readiness>,
+}
+
+impl ScheduledIo {
+    fn slot(&self, dir: Direction) -> &Mutex<Readiness> {
+        match dir {
+            Direction::Read => &self.read,
+            Direction::Write => &self.write,
+        }
+    }
+
+    fn set_ready(&self, dir: Direction) {
+        let waker = {
+            let mut slot = self.slot(dir).lock().unwrap();
+            slot.ready = true;
+            slot.tick = slot.tick.wrapping_add(1);
+            slot.waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+fn cvt(ret: libc::c_int) -> io::Result<libc::c_int> {
+    if ret == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(ret)
+    }
+}
+
+impl Reactor {
+    fn get() -> &'static Reactor {
+        static REACTOR: OnceLock<Reactor> = OnceLock::new();
+        REACTOR.get_or_init(|| {
+            let epoll = cvt(unsafe { libc::epoll_create1(libc::EPOLL_CLOEXEC) })
+                .expect("failed to create the epoll instance");
+            // SAFETY: `epoll_create1` returned a fresh descriptor.
+            let epoll = unsafe { OwnedFd::from_raw_fd(epoll) };
+            thread::Builder::new()
+                .name("futures-io-reactor".into())
+                .spawn(|| Reactor::get().run())
+                .expect("failed to spawn the reactor thread");
+            Reactor {
+                epoll,
+                sources: Mutex::new(HashMap::new()),
+                next_token: AtomicU64::new(0),
+            }
+        })
+    }
+
+    fn run(&self) {
+        let mut events: Vec<libc::epoll_event> = Vec::with_capacity(256);
+        loop {
+            let n = unsafe {
+                libc::epoll_wait(
+                    self.epoll.as_raw_fd(),
+                    events.as_mut_ptr(),
+                    events.capacity() as libc::c_int,
+                    -1,
+                )
+            };
+            let n = match cvt(n) {
+                Ok(n) => n as usize,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => panic!("epoll_wait failed: {}", e),
+            };
+            // SAFETY: the kernel initialized the first `n` events.
+            unsafe { events.set_len(n) };
+
+            for event in events.drain(..) {
+                let token = event.u64;
+                let flags = event.events as libc::c_int;
+                let io = self.sources.lock().unwrap().get(&token).cloned();
+                let Some(io) = io else {
+                    // Deregistered after the event was queued.
+                    continue;
+                };
+                let closed = libc::EPOLLHUP | libc::EPOLLERR;
+                if flags & (libc::EPOLLIN | libc::EPOLLPRI | libc::EPOLLRDHUP | closed) != 0 {
+                    io.set_ready(Direction::Read);
+                }
+                if flags & (libc::EPOLLOUT | closed) != 0 {
+                    io.set_ready(Direction::Write);
+                }
+            }
+        }
+    }
+}
+
+/// A file descriptor registered with the reactor.
+///
+/// The descriptor is deregistered when this is dropped, so it has to be
+/// dropped before the descriptor is closed.
+#[derive(Debug)]
+pub(crate) struct Registration {
+    token: u64,
+    fd: RawFd,
+    io: Arc<ScheduledIo>,
+}
+
+impl Registration {
+    pub(crate) fn new(fd: RawFd) -> io::Result<Self> {
+        let reactor = Reactor::get();
+        let token = reactor.next_token.fetch_add(1, Ordering::Relaxed);
+        let io = Arc::new(ScheduledIo::default());
+        reactor.sources.lock().unwrap().insert(token, io.clone());
+
+        let mut event = libc::epoll_event {
+            events: (libc::EPOLLIN
+                | libc::EPOLLOUT
+                | libc::EPOLLRDHUP
+                | libc::EPOLLPRI
+                | libc::EPOLLET) as u32,
+            u64: token,
+        };
+        let added = cvt(unsafe {
+            libc::epoll_ctl(
+                reactor.epoll.as_raw_fd(),
+                libc::EPOLL_CTL_ADD,
+                fd,
+                &mut event,
+            )
+        });
+        if let Err(e) = added {
+            reactor.sources.lock().unwrap().remove(&token);
+            return Err(e);
+        }
+        Ok(Self { token, fd, io })
+    }
+
+    /// Polls for readiness in the given direction, returning the readiness
+    /// tick to pass to [`clear_ready`](Registration::clear_ready).
+    ///
+    /// Only the waker of the most recent call per direction is woken.
+    pub(crate) fn poll_ready(&self, ctx: &mut Context<'_>, dir: Direction) -> Poll<u64> {
+        let mut slot = self.io.slot(dir).lock().unwrap();
+        if slot.ready {
+            return Poll::Ready(slot.tick);
+        }
+        if !slot
+            .waker
+            .as_ref()
+            .is_some_and(|w| w.will_wake(ctx.waker()))
+        {
+            slot.waker = Some(ctx.waker().clone());
+        }
+        Poll::Pending
+    }
+
+    /// Marks the direction as not ready, unless an event arrived since
+    /// `tick` was observed.
+    pub(crate) fn clear_ready(&self, dir: Direction, tick: u64) {
+        let mut slot = self.io.slot(dir).lock().unwrap();
+        if slot.tick == tick {
+            slot.ready = false;
+        }
+    }
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        let reactor = Reactor::get();
+        // Failure means the descriptor is already gone, which removed it
+        // from the epoll set too.
+        unsafe {
+            libc::epoll_ctl(
+                reactor.epoll.as_raw_fd(),
+                libc::EPOLL_CTL_DEL,
+                self.fd,
+                std::ptr::null_mut(),
+            );
+        }
+        reactor.sources.lock().unwrap().remove(&self.token);
+    }
+}
+
+/// A non-blocking I/O object driven by the reactor.
+#[derive(Debug)]
+pub(crate) struct Evented<T: AsRawFd> {
+    // Declared first so that it is deregistered before `io` closes the
+    // descriptor.
+    registration: Registration,
+    io: T,
+}
+
+impl<T: AsRawFd> Evented<T> {
+    /// Registers `io`, which must already be in non-blocking mode.
+    pub(crate) fn new(io: T) -> io::Result<Self> {
+        Ok(Self {
+            registration: Registration::new(io.as_raw_fd())?,
+            io,
+        })
+    }
+
+    pub(crate) fn get_ref(&self) -> &T {
+        &self.io
+    }
+
+    /// Waits until the direction is ready without performing any I/O.
+    pub(crate) fn poll_ready(&self, ctx: &mut Context<'_>, dir: Direction) -> Poll<()> {
+        self.registration.poll_ready(ctx, dir).map(drop)
+    }
+
+    /// Runs `op` whenever the direction is ready, until it stops failing with
+    /// `WouldBlock`.
+    pub(crate) fn poll_io<R>(
+        &self,
+        ctx: &mut Context<'_>,
+        dir: Direction,
+        mut op: impl FnMut(&T) -> io::Result<R>,
+    ) -> Poll<io::Result<R>> {
+        loop {
+            let tick = match self.registration.poll_ready(ctx, dir) {
+                Poll::Ready(tick) => tick,
+                Poll::Pending => return Poll::Pending,
+            };
+            match op(&self.io) {
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                    self.registration.clear_ready(dir, tick);
+                }
+                res => return Poll::Ready(res),
+            }
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use super::{DecodeBuffer, Decoder, Encoder};
+use std::io;
+use std::vec::Vec;
+
+/// A codec that passes bytes through unchanged.
+///
+/// Decoding yields whatever bytes were read, in chunks of arbitrary size.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct BytesCodec(());
+
+impl BytesCodec {
+    /// Creates a new `BytesCodec`.
+    pub fn new() -> Self {
+        Self(())
+    }
+}
+
+impl Decoder for BytesCodec {
+    type Item = Vec<u8>;
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut DecodeBuffer) -> io::Result<Option<Vec<u8>>> {
+        if src.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(src.split_to(src.len())))
+    }
+}
+
+impl Encoder<Vec<u8>> for BytesCodec {
+    type Error = io::Error;
+
+    fn encode(&mut self, item: Vec<u8>, dst: &mut Vec<u8>) -> io::Result<()> {
+        dst.extend_from_slice(&item);
+        Ok(())
+    }
+}
+
+impl Encoder<&[u8]> for BytesCodec {
+    type Error = io::Error;
+
+    fn encode(&mut self, item: &[u8], dst: &mut Vec<u8>) -> io::Result<()> {
+        dst.extend_from_slice(item);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BytesCodec;
+    use crate::codec::FramedRead;
+    use crate::io::tests::{trickle, wait};
+    use crate::stream::StreamExt;
+    use std::vec::Vec;
+
+    #[test]
+    fn yields_chunks_as_read() {
+        let chunks: Vec<_> = wait(
+            FramedRead::new(trickle(b"abcdefg", 3), BytesCodec::new())
+                .map(Result::unwrap)
+                .collect::<Vec<_>>(),
+        );
+        assert_eq!(chunks, [&b"abc"[..], b"def", b"g"]);
+    }
+}
@@ -0,0 +1,147 @@
+use core::fmt;
+use core::ops::Deref;
+use std::vec::Vec;
+
+/// The bytes a [`Decoder`](super::Decoder) decodes frames from.
+///
+/// Dereferences to the bytes that weren't decoded yet. Taking bytes off the
+/// front with [`advance`](DecodeBuffer::advance) or
+/// [`split_to`](DecodeBuffer::split_to) only moves an offset; the bytes
+/// left over are moved to the front once, before the next read into the
+/// buffer.
+#[derive(Clone, Default)]
+pub struct DecodeBuffer {
+    // Everything up to `buf.len()` is initialized, so that reads can go into
+    // `buf[end..]` without zeroing it first.
+    buf: Vec<u8>,
+    start: usize,
+    end: usize,
+}
+
+impl DecodeBuffer {
+    /// Creates an empty buffer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Removes the first `n` bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer holds fewer than `n` bytes.
+    pub fn advance(&mut self, n: usize) {
+        assert!(n <= self.len(), "advanced past the end of the buffer");
+        self.start += n;
+        if self.start == self.end {
+            self.start = 0;
+            self.end = 0;
+        }
+    }
+
+    /// Removes the first `n` bytes and returns them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer holds fewer than `n` bytes.
+    pub fn split_to(&mut self, n: usize) -> Vec<u8> {
+        let bytes = self[..n].to_vec();
+        self.advance(n);
+        bytes
+    }
+
+    /// Removes all bytes.
+    pub fn clear(&mut self) {
+        self.start = 0;
+        self.end = 0;
+    }
+
+    /// Appends `bytes`.
+    pub fn extend_from_slice(&mut self, bytes: &[u8]) {
+        self.spare_mut(bytes.len())[..bytes.len()].copy_from_slice(bytes);
+        self.end += bytes.len();
+    }
+
+    /// Makes room for at least `additional` more bytes, to be read into the
+    /// buffer later.
+    pub fn reserve(&mut self, additional: usize) {
+        self.spare_mut(additional);
+    }
+
+    /// Returns the room after the buffered bytes, which is at least `min`
+    /// bytes long. Bytes written there are added with
+    /// [`filled`](DecodeBuffer::filled).
+    pub(super) fn spare_mut(&mut self, min: usize) -> &mut [u8] {
+        if self.start > 0 {
+            self.buf.copy_within(self.start..self.end, 0);
+            self.end -= self.start;
+            self.start = 0;
+        }
+        if self.buf.len() - self.end < min {
+            self.buf.resize(self.end + min, 0);
+        }
+        &mut self.buf[self.end..]
+    }
+
+    /// Adds the first `n` bytes of [`spare_mut`](DecodeBuffer::spare_mut)
+    /// to the buffered bytes.
+    pub(super) fn filled(&mut self, n: usize) {
+        assert!(n <= self.buf.len() - self.end);
+        self.end += n;
+    }
+}
+
+impl Deref for DecodeBuffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.buf[self.start..self.end]
+    }
+}
+
+impl From<&[u8]> for DecodeBuffer {
+    fn from(bytes: &[u8]) -> Self {
+        let mut buf = Self::new();
+        buf.extend_from_slice(bytes);
+        buf
+    }
+}
+
+impl From<Vec<u8>> for DecodeBuffer {
+    fn from(buf: Vec<u8>) -> Self {
+        let end = buf.len();
+        Self { buf, start: 0, end }
+    }
+}
+
+impl fmt::Debug for DecodeBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DecodeBuffer")
+            .field("bytes", &&**self)
+            .field("capacity", &self.buf.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DecodeBuffer;
+
+    #[test]
+    fn consumes_from_the_front_and_compacts_before_reading() {
+        let mut buf = DecodeBuffer::from(&b"hello world"[..]);
+        buf.advance(2);
+        assert_eq!(buf.split_to(4), b"llo ");
+        assert_eq!(&*buf, b"world");
+
+        // The spare room is kept, and only the leftover bytes move.
+        let spare = buf.spare_mut(16);
+        assert!(spare.len() >= 16);
+        spare[..3].copy_from_slice(b"!!!");
+        buf.filled(3);
+        assert_eq!(&*buf, b"world!!!");
+        let capacity = buf.buf.len();
+        buf.advance(8);
+        assert!(buf.is_empty());
+        assert_eq!(buf.spare_mut(16).len(), capacity);
+    }
+}
@@ -0,0 +1,237 @@
+use super::{DecodeBuffer, Decoder, CHUNK_SIZE};
+use core::pin::Pin;
+use futures_core::ready;
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll};
+use futures_io::AsyncRead;
+use pin_project_lite::pin_project;
+
+pin_project! {
+    /// A [`Stream`] of the frames that a [`Decoder`] finds in the bytes of an
+    /// [`AsyncRead`].
+    ///
+    /// Bytes that don't form a complete frame yet are buffered until more
+    /// arrive. At end-of-file, the remaining bytes go to
+    /// [`Decoder::decode_eof`].
+    ///
+    /// Decoding errors are yielded as they come, and the stream continues
+    /// afterwards, so that decoders can skip bad frames. An I/O error, or a
+    /// decoding error at end-of-file, ends the stream.
+    #[derive(Debug)]
+    #[must_use = "streams do nothing unless polled"]
+    pub struct FramedRead<R, D> {
+        #[pin]
+        inner: R,
+        decoder: D,
+        buffer: DecodeBuffer,
+        // Whether `buffer` may hold a frame that wasn't decoded yet.
+        readable: bool,
+        eof: bool,
+        done: bool,
+    }
+}
+
+impl<R: AsyncRead, D: Decoder> FramedRead<R, D> {
+    /// Creates a stream of the frames `decoder` decodes from `inner`.
+    pub fn new(inner: R, decoder: D) -> Self {
+        Self {
+            inner,
+            decoder,
+            buffer: DecodeBuffer::new(),
+            readable: false,
+            eof: false,
+            done: false,
+        }
+    }
+}
+
+impl<R, D> FramedRead<R, D> {
+    /// Returns a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying reader.
+    ///
+    /// Reading from it directly skips over the buffered bytes.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Returns a pinned mutable reference to the underlying reader.
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut R> {
+        self.project().inner
+    }
+
+    /// Consumes the stream, returning the underlying reader.
+    ///
+    /// Any buffered bytes are lost.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Returns a reference to the decoder.
+    pub fn decoder(&self) -> &D {
+        &self.decoder
+    }
+
+    /// Returns a mutable reference to the decoder.
+    pub fn decoder_mut(&mut self) -> &mut D {
+        &mut self.decoder
+    }
+
+    /// Returns the bytes that were read but not decoded yet.
+    pub fn read_buffer(&self) -> &[u8] {
+        &self.buffer
+    }
+}
+
+impl<R: AsyncRead, D: Decoder> Stream for FramedRead<R, D> {
+    type Item = Result<D::Item, D::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+        loop {
+            if *this.done {
+                return Poll::Ready(None);
+            }
+
+            if *this.readable {
+                let frame = if *this.eof {
+                    this.decoder.decode_eof(this.buffer)
+                } else {
+                    this.decoder.decode(this.buffer)
+                };
+                match frame {
+                    Ok(Some(frame)) => return Poll::Ready(Some(Ok(frame))),
+                    Ok(None) if *this.eof => {
+                        *this.done = true;
+                        return Poll::Ready(None);
+                    }
+                    Ok(None) => *this.readable = false,
+                    Err(e) => {
+                        // Nothing new will come at end-of-file, so retrying
+                        // would fail the same way.
+                        *this.done = *this.eof;
+                        return Poll::Ready(Some(Err(e)));
+                    }
+                }
+            }
+
+            let spare = this.buffer.spare_mut(CHUNK_SIZE);
+            match ready!(this.inner.as_mut().poll_read(ctx, spare)) {
+                Ok(n) => {
+                    this.buffer.filled(n);
+                    *this.eof = n == 0;
+                    *this.readable = true;
+                }
+                Err(e) => {
+                    *this.done = true;
+                    return Poll::Ready(Some(Err(e.into())));
+                }
+            }
+        }
+    }
+}
+
+impl<R: AsyncRead, D: Decoder> FusedStream for FramedRead<R, D> {
+    fn is_terminated(&self) -> bool {
+        self.done
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FramedRead;
+    use crate::codec::{BytesCodec, DecodeBuffer, Decoder, LinesCodec};
+    use crate::future::FutureExt;
+    use crate::io::tests::{trickle, wait};
+    use crate::io::ErrorKind;
+    use crate::stream::StreamExt;
+    use core::pin::Pin;
+    use core::task::{Context, Poll};
+    use futures_core::stream::FusedStream;
+    use futures_io::AsyncRead;
+    use std::io;
+    use std::vec::Vec;
+
+    /// Decodes single bytes, failing on zeros.
+    struct NoZeros;
+
+    impl Decoder for NoZeros {
+        type Item = u8;
+        type Error = io::Error;
+
+        fn decode(&mut self, src: &mut DecodeBuffer) -> io::Result<Option<u8>> {
+            let byte = match src.first().copied() {
+                Some(byte) => byte,
+                None => return Ok(None),
+            };
+            src.advance(1);
+            if byte == 0 {
+                return Err(io::ErrorKind::InvalidData.into());
+            }
+            Ok(Some(byte))
+        }
+    }
+
+    #[test]
+    fn buffers_partial_frames_across_pending() {
+        let mut framed = FramedRead::new(trickle(b"first\nsecond\nthi", 3), LinesCodec::new());
+        let mut polls = 0;
+        let mut lines = Vec::new();
+        loop {
+            polls += 1;
+            match framed.next().now_or_never() {
+                Some(Some(line)) => lines.push(line.unwrap()),
+                Some(None) => break,
+                None => {}
+            }
+        }
+        assert!(polls > 10);
+        assert_eq!(lines, ["first", "second", "thi"]);
+        assert!(framed.is_terminated());
+        assert!(wait(framed.next()).is_none());
+    }
+
+    #[test]
+    fn continues_after_decoding_errors() {
+        let frames: Vec<_> = wait(FramedRead::new(&[1, 0, 2][..], NoZeros).collect::<Vec<_>>());
+        assert_eq!(frames.len(), 3);
+        assert_eq!(frames[0].as_ref().unwrap(), &1);
+        assert_eq!(
+            frames[1].as_ref().unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+        assert_eq!(frames[2].as_ref().unwrap(), &2);
+    }
+
+    #[test]
+    fn io_errors_end_the_stream() {
+        struct Broken;
+
+        impl AsyncRead for Broken {
+            fn poll_read(
+                self: Pin<&mut Self>,
+                _: &mut Context<'_>,
+                _: &mut [u8],
+            ) -> Poll<io::Result<usize>> {
+                Poll::Ready(Err(io::ErrorKind::ConnectionReset.into()))
+            }
+        }
+
+        let mut framed = FramedRead::new(Broken, BytesCodec::new());
+        let err = wait(framed.next()).unwrap().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ConnectionReset);
+        assert!(wait(framed.next()).is_none());
+    }
+
+    #[test]
+    fn leftover_bytes_at_end_of_file() {
+        let mut framed =
+            FramedRead::new(&b"\x01\x00"[..], crate::codec::LengthDelimitedCodec::new());
+        let err = wait(framed.next()).unwrap().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+        assert!(wait(framed.next()).is_none());
+    }
+}
@@ -0,0 +1,175 @@
+use super::{Encoder, CHUNK_SIZE};
+use core::pin::Pin;
+use futures_core::ready;
+use futures_core::sink::Sink;
+use futures_core::task::{Context, Poll};
+use futures_io::AsyncWrite;
+use pin_project_lite::pin_project;
+use std::io;
+use std::vec::Vec;
+
+pin_project! {
+    /// A [`Sink`] that encodes frames with an [`Encoder`] and writes them to
+    /// an [`AsyncWrite`].
+    ///
+    /// Encoded frames are buffered, and written out when more than 8 KiB
+    /// piled up, or when the sink is flushed or closed.
+    #[derive(Debug)]
+    pub struct FramedWrite<W, E> {
+        #[pin]
+        inner: W,
+        encoder: E,
+        buffer: Vec<u8>,
+    }
+}
+
+impl<W: AsyncWrite, E> FramedWrite<W, E> {
+    /// Creates a sink that writes the frames `encoder` encodes to `inner`.
+    pub fn new(inner: W, encoder: E) -> Self {
+        Self {
+            inner,
+            encoder,
+            buffer: Vec::with_capacity(CHUNK_SIZE),
+        }
+    }
+}
+
+impl<W, E> FramedWrite<W, E> {
+    /// Returns a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying writer.
+    ///
+    /// Writing to it directly skips ahead of the buffered frames.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Returns a pinned mutable reference to the underlying writer.
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut W> {
+        self.project().inner
+    }
+
+    /// Consumes the sink, returning the underlying writer.
+    ///
+    /// Any buffered frames are lost.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    /// Returns a reference to the encoder.
+    pub fn encoder(&self) -> &E {
+        &self.encoder
+    }
+
+    /// Returns a mutable reference to the encoder.
+    pub fn encoder_mut(&mut self) -> &mut E {
+        &mut self.encoder
+    }
+
+    /// Returns the encoded bytes that weren't written yet.
+    pub fn write_buffer(&self) -> &[u8] {
+        &self.buffer
+    }
+}
+
+impl<W: AsyncWrite, E> FramedWrite<W, E> {
+    /// Writes out the buffer, without flushing the writer.
+    fn poll_write_buffer(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let mut this = self.project();
+        let mut written = 0;
+        let res = loop {
+            if written == this.buffer.len() {
+                break Poll::Ready(Ok(()));
+            }
+            match this.inner.as_mut().poll_write(ctx, &this.buffer[written..]) {
+                Poll::Ready(Ok(0)) => {
+                    break Poll::Ready(Err(io::Error::new(
+                        io::ErrorKind::WriteZero,
+                        "failed to write frame to writer",
+                    )))
+                }
+                Poll::Ready(Ok(n)) => written += n,
+                Poll::Ready(Err(e)) => break Poll::Ready(Err(e)),
+                Poll::Pending => break Poll::Pending,
+            }
+        };
+        this.buffer.drain(..written);
+        res
+    }
+}
+
+impl<W: AsyncWrite, E: Encoder<Item>, Item> Sink<Item> for FramedWrite<W, E> {
+    type Error = E::Error;
+
+    fn poll_ready(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Result<(), E::Error>> {
+        if self.buffer.len() >= CHUNK_SIZE {
+            ready!(self.poll_write_buffer(ctx))?;
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: Item) -> Result<(), E::Error> {
+        let this = self.project();
+        this.encoder.encode(item, this.buffer)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Result<(), E::Error>> {
+        ready!(self.as_mut().poll_write_buffer(ctx))?;
+        ready!(self.project().inner.poll_flush(ctx))?;
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Result<(), E::Error>> {
+        ready!(self.as_mut().poll_write_buffer(ctx))?;
+        ready!(self.project().inner.poll_close(ctx))?;
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FramedWrite;
+    use crate::codec::tests::send_all;
+    use crate::codec::BytesCodec;
+    use crate::io::tests::{trickle, wait};
+    use core::future::poll_fn;
+    use core::pin::Pin;
+    use futures_core::Sink;
+    use std::vec;
+    use std::vec::Vec;
+
+    #[test]
+    fn buffers_until_flushed() {
+        let mut framed = FramedWrite::new(trickle(b"", 5), BytesCodec::new());
+        send_all(&mut framed, [&b"abc"[..], b"defgh", b"ij"]).unwrap();
+        assert_eq!(framed.get_ref().data, b"abcdefghij");
+        assert!(framed.write_buffer().is_empty());
+    }
+
+    #[test]
+    fn writes_out_when_the_buffer_fills_up() {
+        let mut framed = FramedWrite::new(trickle(b"", 1 << 20), BytesCodec::new());
+        for _ in 0..3 {
+            wait(poll_fn(|ctx| {
+                Sink::<Vec<u8>>::poll_ready(Pin::new(&mut framed), ctx)
+            }))
+            .unwrap();
+            Pin::new(&mut framed).start_send(vec![1; 5000]).unwrap();
+        }
+        // The first two frames went out to make room for the third.
+        assert_eq!(framed.get_ref().data.len(), 10_000);
+        assert_eq!(framed.write_buffer().len(), 5000);
+
+        wait(poll_fn(|ctx| {
+            Sink::<Vec<u8>>::poll_close(Pin::new(&mut framed), ctx)
+        }))
+        .unwrap();
+        assert_eq!(framed.get_ref().data, vec![1; 15_000]);
+        // `Trickle` stops accepting bytes once closed.
+        let err = send_all(&mut framed, [Vec::from("x")]).unwrap_err();
+        assert_eq!(err.kind(), crate::io::ErrorKind::WriteZero);
+    }
+}
@@ -0,0 +1,307 @@
+use super::{DecodeBuffer, Decoder, Encoder};
+use std::io;
+use std::vec::Vec;
+
+/// The byte order of a length header.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Endianness {
+    /// Most significant byte first, as in network protocols.
+    #[default]
+    Big,
+    /// Least significant byte first.
+    Little,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum DecodeState {
+    Head,
+    Data(usize),
+    // Skipping the rest of a frame that was too long.
+    Discard(usize),
+}
+
+/// A codec for frames that start with a header holding their length.
+///
+/// By default, the header is a 4-byte big-endian unsigned integer, and
+/// frames can be up to 8 MiB long, not counting the header.
+///
+/// Decoding a frame that is too long fails with
+/// [`io::ErrorKind::InvalidData`], after which its bytes are skipped, and
+/// decoding continues with the next frame.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LengthDelimitedCodec {
+    length_field_length: usize,
+    endianness: Endianness,
+    max_frame_length: usize,
+    state: DecodeState,
+}
+
+impl LengthDelimitedCodec {
+    /// Creates a `LengthDelimitedCodec` with the default settings.
+    pub fn new() -> Self {
+        Self {
+            length_field_length: 4,
+            endianness: Endianness::Big,
+            max_frame_length: 8 * 1024 * 1024,
+            state: DecodeState::Head,
+        }
+    }
+
+    /// Returns the size of the length header in bytes.
+    pub fn length_field_length(&self) -> usize {
+        self.length_field_length
+    }
+
+    /// Sets the size of the length header in bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `length` isn't between 1 and 8.
+    pub fn set_length_field_length(&mut self, length: usize) {
+        assert!(
+            (1..=8).contains(&length),
+            "length field length must be between 1 and 8"
+        );
+        self.length_field_length = length;
+    }
+
+    /// Returns the byte order of the length header.
+    pub fn endianness(&self) -> Endianness {
+        self.endianness
+    }
+
+    /// Sets the byte order of the length header.
+    pub fn set_endianness(&mut self, endianness: Endianness) {
+        self.endianness = endianness;
+    }
+
+    /// Returns the maximum frame length.
+    pub fn max_frame_length(&self) -> usize {
+        self.max_frame_length
+    }
+
+    /// Sets the maximum frame length, not counting the header.
+    pub fn set_max_frame_length(&mut self, max: usize) {
+        self.max_frame_length = max;
+    }
+
+    fn decode_head(&mut self, src: &mut DecodeBuffer) -> io::Result<Option<usize>> {
+        let n = self.length_field_length;
+        if src.len() < n {
+            return Ok(None);
+        }
+        let mut bytes = [0; 8];
+        let len = match self.endianness {
+            Endianness::Big => {
+                bytes[8 - n..].copy_from_slice(&src[..n]);
+                u64::from_be_bytes(bytes)
+            }
+            Endianness::Little => {
+                bytes[..n].copy_from_slice(&src[..n]);
+                u64::from_le_bytes(bytes)
+            }
+        };
+        src.advance(n);
+
+        match usize::try_from(len) {
+            Ok(len) if len <= self.max_frame_length => Ok(Some(len)),
+            _ => {
+                self.state = DecodeState::Discard(usize::try_from(len).unwrap_or(usize::MAX));
+                Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "frame exceeds max frame length",
+                ))
+            }
+        }
+    }
+
+    fn encode_slice(&mut self, item: &[u8], dst: &mut Vec<u8>) -> io::Result<()> {
+        let n = self.length_field_length;
+        let len = item.len();
+        if len > self.max_frame_length || (n < 8 && len as u64 >= 1 << (n * 8)) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "frame exceeds max frame length",
+            ));
+        }
+        let len = len as u64;
+        dst.reserve(n + item.len());
+        match self.endianness {
+            Endianness::Big => dst.extend_from_slice(&len.to_be_bytes()[8 - n..]),
+            Endianness::Little => dst.extend_from_slice(&len.to_le_bytes()[..n]),
+        }
+        dst.extend_from_slice(item);
+        Ok(())
+    }
+}
+
+impl Default for LengthDelimitedCodec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Decoder for LengthDelimitedCodec {
+    type Item = Vec<u8>;
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut DecodeBuffer) -> io::Result<Option<Vec<u8>>> {
+        loop {
+            match self.state {
+                DecodeState::Head => match self.decode_head(src)? {
+                    Some(len) => self.state = DecodeState::Data(len),
+                    None => return Ok(None),
+                },
+                DecodeState::Data(len) => {
+                    if src.len() < len {
+                        src.reserve(len - src.len());
+                        return Ok(None);
+                    }
+                    self.state = DecodeState::Head;
+                    return Ok(Some(src.split_to(len)));
+                }
+                DecodeState::Discard(remaining) => {
+                    let n = remaining.min(src.len());
+                    src.advance(n);
+                    if n < remaining {
+                        self.state = DecodeState::Discard(remaining - n);
+                        return Ok(None);
+                    }
+                    self.state = DecodeState::Head;
+                }
+            }
+        }
+    }
+
+    fn decode_eof(&mut self, src: &mut DecodeBuffer) -> io::Result<Option<Vec<u8>>> {
+        match self.decode(src)? {
+            Some(frame) => Ok(Some(frame)),
+            None if src.is_empty() && self.state == DecodeState::Head => Ok(None),
+            // The rest of a frame that was too long is gone for good.
+            None if matches!(self.state, DecodeState::Discard(_)) => {
+                self.state = DecodeState::Head;
+                Ok(None)
+            }
+            None => {
+                src.clear();
+                self.state = DecodeState::Head;
+                Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "bytes remaining at end of stream",
+                ))
+            }
+        }
+    }
+}
+
+impl Encoder<Vec<u8>> for LengthDelimitedCodec {
+    type Error = io::Error;
+
+    fn encode(&mut self, item: Vec<u8>, dst: &mut Vec<u8>) -> io::Result<()> {
+        self.encode_slice(&item, dst)
+    }
+}
+
+impl Encoder<&[u8]> for LengthDelimitedCodec {
+    type Error = io::Error;
+
+    fn encode(&mut self, item: &[u8], dst: &mut Vec<u8>) -> io::Result<()> {
+        self.encode_slice(item, dst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Endianness, LengthDelimitedCodec};
+    use crate::codec::tests::send_all;
+    use crate::codec::{Encoder, FramedRead, FramedWrite};
+    use crate::io::tests::{trickle, wait};
+    use crate::io::ErrorKind;
+    use crate::stream::StreamExt;
+    use std::vec;
+    use std::vec::Vec;
+
+    #[test]
+    fn round_trips_frames() {
+        for (width, endianness) in [
+            (1, Endianness::Big),
+            (3, Endianness::Little),
+            (8, Endianness::Big),
+        ] {
+            let mut codec = LengthDelimitedCodec::new();
+            codec.set_length_field_length(width);
+            codec.set_endianness(endianness);
+
+            let frames = [vec![], vec![7; 200], b"frame".to_vec()];
+            let mut framed = FramedWrite::new(Vec::new(), codec.clone());
+            send_all(&mut framed, frames.clone()).unwrap();
+            let encoded = framed.into_inner();
+            assert_eq!(encoded.len(), 3 * width + 205);
+
+            let decoded: Vec<_> = wait(
+                FramedRead::new(trickle(&encoded, 7), codec)
+                    .map(Result::unwrap)
+                    .collect::<Vec<_>>(),
+            );
+            assert_eq!(decoded, frames);
+        }
+    }
+
+    #[test]
+    fn encodes_headers() {
+        let mut codec = LengthDelimitedCodec::new();
+        let mut buf = Vec::new();
+        codec.encode(&b"ab"[..], &mut buf).unwrap();
+        assert_eq!(buf, b"\x00\x00\x00\x02ab");
+
+        codec.set_length_field_length(2);
+        codec.set_endianness(Endianness::Little);
+        buf.clear();
+        codec.encode(&b"ab"[..], &mut buf).unwrap();
+        assert_eq!(buf, b"\x02\x00ab");
+
+        codec.set_length_field_length(1);
+        let err = codec.encode(vec![0; 256], &mut buf).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn skips_frames_that_are_too_long() {
+        let mut codec = LengthDelimitedCodec::new();
+        codec.set_length_field_length(2);
+        codec.set_max_frame_length(4);
+        let input = b"\x00\x02ok\x00\x07toolong\x00\x03end\x00\x09abc";
+
+        let frames: Vec<_> = wait(FramedRead::new(trickle(input, 3), codec).collect::<Vec<_>>());
+        assert_eq!(frames.len(), 4);
+        assert_eq!(frames[0].as_ref().unwrap(), b"ok");
+        assert_eq!(
+            frames[1].as_ref().unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+        assert_eq!(frames[2].as_ref().unwrap(), b"end");
+        assert_eq!(
+            frames[3].as_ref().unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+    }
+
+    #[test]
+    fn truncated_frames_fail_at_end_of_file() {
+        let frames: Vec<_> = wait(
+            FramedRead::new(&b"\x00\x00\x00\x04abc"[..], LengthDelimitedCodec::new())
+                .collect::<Vec<_>>(),
+        );
+        assert_eq!(frames.len(), 1);
+        assert_eq!(
+            frames[0].as_ref().unwrap_err().kind(),
+            ErrorKind::UnexpectedEof
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "between 1 and 8")]
+    fn rejects_invalid_header_widths() {
+        LengthDelimitedCodec::new().set_length_field_length(9);
+    }
+}
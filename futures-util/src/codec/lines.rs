@@ -0,0 +1,230 @@
+use super::{DecodeBuffer, Decoder, Encoder};
+use core::fmt;
+use std::io;
+use std::string::String;
+use std::vec::Vec;
+
+/// A codec for lines of UTF-8 text.
+///
+/// Decoded lines don't include their `\n` or `\r\n` ending. At end-of-file,
+/// a last line without an ending is yielded too. Encoding appends `\n` to
+/// each line.
+///
+/// Lines longer than the maximum length fail with
+/// [`LinesCodecError::MaxLineLengthExceeded`], after which the rest of the
+/// line is skipped, and decoding continues with the next one.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LinesCodec {
+    // Where to continue looking for a newline, since everything before was
+    // checked already.
+    next_index: usize,
+    max_length: usize,
+    discarding: bool,
+}
+
+impl LinesCodec {
+    /// Creates a `LinesCodec` without a maximum line length.
+    ///
+    /// A peer that never sends a newline can make it buffer without bound;
+    /// prefer [`LinesCodec::new_with_max_length`] for untrusted input.
+    pub fn new() -> Self {
+        Self::new_with_max_length(usize::MAX)
+    }
+
+    /// Creates a `LinesCodec` that fails on lines longer than `max_length`
+    /// bytes, not counting the `\n` but counting a `\r` before it.
+    pub fn new_with_max_length(max_length: usize) -> Self {
+        Self {
+            next_index: 0,
+            max_length,
+            discarding: false,
+        }
+    }
+
+    /// Returns the maximum line length.
+    pub fn max_length(&self) -> usize {
+        self.max_length
+    }
+}
+
+impl Default for LinesCodec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Removes the first `len` bytes from `src`, and converts them to a line
+/// without its `\r\n` or `\n` ending.
+fn take_line(src: &mut DecodeBuffer, len: usize) -> Result<String, LinesCodecError> {
+    let mut line = src.split_to(len);
+    if line.last() == Some(&b'\n') {
+        line.pop();
+    }
+    if line.last() == Some(&b'\r') {
+        line.pop();
+    }
+    String::from_utf8(line)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "line is not valid UTF-8").into())
+}
+
+impl Decoder for LinesCodec {
+    type Item = String;
+    type Error = LinesCodecError;
+
+    fn decode(&mut self, src: &mut DecodeBuffer) -> Result<Option<String>, LinesCodecError> {
+        loop {
+            // A line can't be longer than this, newline included.
+            let read_to = src.len().min(self.max_length.saturating_add(1));
+            let newline = src[self.next_index..read_to]
+                .iter()
+                .position(|&b| b == b'\n')
+                .map(|i| self.next_index + i);
+
+            match (self.discarding, newline) {
+                (true, Some(i)) => {
+                    src.advance(i + 1);
+                    self.discarding = false;
+                    self.next_index = 0;
+                }
+                (true, None) => {
+                    src.advance(read_to);
+                    self.next_index = 0;
+                    if src.is_empty() {
+                        return Ok(None);
+                    }
+                }
+                (false, Some(i)) => {
+                    self.next_index = 0;
+                    return take_line(src, i + 1).map(Some);
+                }
+                (false, None) if src.len() > self.max_length => {
+                    self.discarding = true;
+                    return Err(LinesCodecError::MaxLineLengthExceeded);
+                }
+                (false, None) => {
+                    self.next_index = read_to;
+                    return Ok(None);
+                }
+            }
+        }
+    }
+
+    fn decode_eof(&mut self, src: &mut DecodeBuffer) -> Result<Option<String>, LinesCodecError> {
+        if let Some(line) = self.decode(src)? {
+            return Ok(Some(line));
+        }
+        self.next_index = 0;
+        if src.is_empty() || src[..] == b"\r"[..] {
+            src.clear();
+            return Ok(None);
+        }
+        let len = src.len();
+        take_line(src, len).map(Some)
+    }
+}
+
+impl<T: AsRef<str>> Encoder<T> for LinesCodec {
+    type Error = LinesCodecError;
+
+    fn encode(&mut self, line: T, dst: &mut Vec<u8>) -> Result<(), LinesCodecError> {
+        let line = line.as_ref();
+        dst.reserve(line.len() + 1);
+        dst.extend_from_slice(line.as_bytes());
+        dst.push(b'\n');
+        Ok(())
+    }
+}
+
+/// The error type of [`LinesCodec`].
+#[derive(Debug)]
+pub enum LinesCodecError {
+    /// A line was longer than the maximum length.
+    MaxLineLengthExceeded,
+    /// An I/O error, or a line that wasn't valid UTF-8.
+    Io(io::Error),
+}
+
+impl fmt::Display for LinesCodecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MaxLineLengthExceeded => f.write_str("max line length exceeded"),
+            Self::Io(e) => fmt::Display::fmt(e, f),
+        }
+    }
+}
+
+impl std::error::Error for LinesCodecError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::MaxLineLengthExceeded => None,
+            Self::Io(e) => Some(e),
+        }
+    }
+}
+
+impl From<io::Error> for LinesCodecError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LinesCodec, LinesCodecError};
+    use crate::codec::{DecodeBuffer, Decoder, FramedRead};
+    use crate::io::tests::{trickle, wait};
+    use crate::stream::StreamExt;
+    use std::string::{String, ToString};
+    use std::vec::Vec;
+
+    fn decode_all(codec: LinesCodec, input: &[u8], step: usize) -> Vec<Result<String, String>> {
+        wait(
+            FramedRead::new(trickle(input, step), codec)
+                .map(|line| line.map_err(|e| e.to_string()))
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    #[test]
+    fn splits_lines() {
+        let input = b"one\r\ntwo\n\nlast";
+        for step in [1, 2, 100] {
+            let lines = decode_all(LinesCodec::new(), input, step);
+            let expected: Vec<Result<String, String>> = ["one", "two", "", "last"]
+                .map(|line| Ok(line.into()))
+                .into();
+            assert_eq!(lines, expected, "step {step}");
+        }
+    }
+
+    #[test]
+    fn skips_lines_that_are_too_long() {
+        let input = b"12345\n1234\r\n123456\nok\n1234567890\nend";
+        for step in [1, 3, 100] {
+            let lines = decode_all(LinesCodec::new_with_max_length(5), input, step);
+            assert_eq!(
+                lines,
+                [
+                    Ok("12345".into()),
+                    Ok("1234".into()),
+                    Err("max line length exceeded".into()),
+                    Ok("ok".into()),
+                    Err("max line length exceeded".into()),
+                    Ok("end".into()),
+                ],
+                "step {step}"
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_utf8() {
+        let mut codec = LinesCodec::new();
+        let mut buf = DecodeBuffer::from(&b"\xff\nok\n"[..]);
+        match codec.decode(&mut buf) {
+            Err(LinesCodecError::Io(e)) => assert_eq!(e.kind(), std::io::ErrorKind::InvalidData),
+            res => panic!("unexpected {res:?}"),
+        }
+        assert_eq!(codec.decode(&mut buf).unwrap().unwrap(), "ok");
+    }
+}
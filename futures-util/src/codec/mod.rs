@@ -0,0 +1,116 @@
+//! Framing of byte streams.
+//!
+//! A [`Decoder`] cuts frames out of the bytes read so far and an
+//! [`Encoder`] turns frames back into bytes. [`FramedRead`] uses a decoder to
+//! turn an [`AsyncRead`](futures_io::AsyncRead) into a [`Stream`] of
+//! frames, and [`FramedWrite`] uses an encoder to turn an
+//! [`AsyncWrite`](futures_io::AsyncWrite) into a [`Sink`] of frames.
+//!
+//! This module comes with [`BytesCodec`], which passes bytes on as they
+//! are, [`LinesCodec`] for text lines, and [`LengthDelimitedCodec`] for
+//! frames that start with their length.
+//!
+//! [`Stream`]: futures_core::Stream
+//! [`Sink`]: futures_core::Sink
+
+use std::io;
+use std::vec::Vec;
+
+mod bytes;
+pub use self::bytes::BytesCodec;
+
+mod decode_buffer;
+pub use self::decode_buffer::DecodeBuffer;
+
+mod framed_read;
+pub use self::framed_read::FramedRead;
+
+mod framed_write;
+pub use self::framed_write::FramedWrite;
+
+mod length_delimited;
+pub use self::length_delimited::{Endianness, LengthDelimitedCodec};
+
+mod lines;
+pub use self::lines::{LinesCodec, LinesCodecError};
+
+/// Decodes frames from a buffer of bytes.
+pub trait Decoder {
+    /// The type of decoded frames.
+    type Item;
+
+    /// The type of decoding errors.
+    ///
+    /// [`FramedRead`] also reports I/O errors through this type.
+    type Error: From<io::Error>;
+
+    /// Decodes a frame from the start of `src`, removing its bytes with
+    /// [`DecodeBuffer::advance`] or [`DecodeBuffer::split_to`].
+    ///
+    /// Returns `Ok(None)` if `src` doesn't hold a complete frame yet; the
+    /// caller then reads more bytes, appends them to `src`, and calls this
+    /// again. Leftover bytes are kept in `src` for the next frame.
+    fn decode(&mut self, src: &mut DecodeBuffer) -> Result<Option<Self::Item>, Self::Error>;
+
+    /// Decodes a frame once no more bytes will come.
+    ///
+    /// This is called repeatedly until it returns `Ok(None)`. By default, it
+    /// decodes normally, and fails with [`io::ErrorKind::UnexpectedEof`] if
+    /// that leaves bytes in `src`.
+    fn decode_eof(&mut self, src: &mut DecodeBuffer) -> Result<Option<Self::Item>, Self::Error> {
+        match self.decode(src)? {
+            Some(frame) => Ok(Some(frame)),
+            None if src.is_empty() => Ok(None),
+            None => Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "bytes remaining at end of stream",
+            )
+            .into()),
+        }
+    }
+}
+
+/// Encodes frames of type `Item` into bytes.
+pub trait Encoder<Item> {
+    /// The type of encoding errors.
+    ///
+    /// [`FramedWrite`] also reports I/O errors through this type.
+    type Error: From<io::Error>;
+
+    /// Appends the encoding of `item` to `dst`.
+    fn encode(&mut self, item: Item, dst: &mut Vec<u8>) -> Result<(), Self::Error>;
+}
+
+/// The size of the chunks [`FramedRead`] reads, and the amount of encoded
+/// data after which [`FramedWrite`] writes it out before taking more.
+const CHUNK_SIZE: usize = 8 * 1024;
+
+#[cfg(test)]
+mod tests {
+    use super::{FramedWrite, LinesCodec};
+    use crate::io::tests::wait;
+    use core::future::poll_fn;
+    use core::pin::Pin;
+    use futures_core::Sink;
+    use std::vec::Vec;
+
+    /// Sends `items` into `sink` and flushes it.
+    pub(super) fn send_all<S, I>(sink: &mut S, items: I) -> Result<(), S::Error>
+    where
+        S: Sink<I::Item> + Unpin,
+        I: IntoIterator,
+    {
+        for item in items {
+            wait(poll_fn(|ctx| Pin::new(&mut *sink).poll_ready(ctx)))?;
+            Pin::new(&mut *sink).start_send(item)?;
+        }
+        wait(poll_fn(|ctx| Pin::new(&mut *sink).poll_flush(ctx)))
+    }
+
+    #[test]
+    fn sink_of_lines() {
+        let mut framed = FramedWrite::new(Vec::new(), LinesCodec::new());
+        send_all(&mut framed, ["one", "two"]).unwrap();
+        assert_eq!(framed.get_ref(), b"one\ntwo\n");
+    }
+}
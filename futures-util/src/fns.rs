@@ -0,0 +1,183 @@
+//! Nameable function objects.
+//!
+//! Closures can't be named in the type of a public adapter, so adapters that
+//! only differ in what they do with an output are built from a shared adapter
+//! and one of the small function types below.
+
+use core::fmt;
+use core::marker::PhantomData;
+
+pub trait FnOnce1<A> {
+    type Output;
+    fn call_once(self, arg: A) -> Self::Output;
+}
+
+impl<T, A, R> FnOnce1<A> for T
+where
+    T: FnOnce(A) -> R,
+{
+    type Output = R;
+    fn call_once(self, arg: A) -> R {
+        self(arg)
+    }
+}
+
+pub trait FnMut1<A>: FnOnce1<A> {
+    fn call_mut(&mut self, arg: A) -> Self::Output;
+}
+
+impl<T, A, R> FnMut1<A> for T
+where
+    T: FnMut(A) -> R,
+{
+    fn call_mut(&mut self, arg: A) -> R {
+        self(arg)
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+pub struct InspectFn<F>(F);
+
+impl<F, A> FnOnce1<A> for InspectFn<F>
+where
+    F: for<'a> FnOnce1<&'a A, Output = ()>,
+{
+    type Output = A;
+    fn call_once(self, arg: A) -> Self::Output {
+        self.0.call_once(&arg);
+        arg
+    }
+}
+
+pub(crate) fn inspect_fn<F>(f: F) -> InspectFn<F> {
+    InspectFn(f)
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+pub struct MapOkFn<F>(F);
+
+impl<F, T, E> FnOnce1<Result<T, E>> for MapOkFn<F>
+where
+    F: FnOnce1<T>,
+{
+    type Output = Result<F::Output, E>;
+    fn call_once(self, arg: Result<T, E>) -> Self::Output {
+        arg.map(|x| self.0.call_once(x))
+    }
+}
+
+impl<F, T, E> FnMut1<Result<T, E>> for MapOkFn<F>
+where
+    F: FnMut1<T>,
+{
+    fn call_mut(&mut self, arg: Result<T, E>) -> Self::Output {
+        arg.map(|x| self.0.call_mut(x))
+    }
+}
+
+pub(crate) fn map_ok_fn<F>(f: F) -> MapOkFn<F> {
+    MapOkFn(f)
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+pub struct MapErrFn<F>(F);
+
+impl<F, T, E> FnOnce1<Result<T, E>> for MapErrFn<F>
+where
+    F: FnOnce1<E>,
+{
+    type Output = Result<T, F::Output>;
+    fn call_once(self, arg: Result<T, E>) -> Self::Output {
+        arg.map_err(|x| self.0.call_once(x))
+    }
+}
+
+impl<F, T, E> FnMut1<Result<T, E>> for MapErrFn<F>
+where
+    F: FnMut1<E>,
+{
+    fn call_mut(&mut self, arg: Result<T, E>) -> Self::Output {
+        arg.map_err(|x| self.0.call_mut(x))
+    }
+}
+
+pub(crate) fn map_err_fn<F>(f: F) -> MapErrFn<F> {
+    MapErrFn(f)
+}
+
+pub struct IntoFn<T>(PhantomData<fn() -> T>);
+
+impl<T> fmt::Debug for IntoFn<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("IntoFn")
+    }
+}
+
+impl<T, A> FnOnce1<A> for IntoFn<T>
+where
+    A: Into<T>,
+{
+    type Output = T;
+    fn call_once(self, arg: A) -> T {
+        arg.into()
+    }
+}
+
+pub(crate) fn into_fn<T>() -> IntoFn<T> {
+    IntoFn(PhantomData)
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+pub struct InspectOkFn<F>(F);
+
+impl<'a, F, T, E> FnOnce1<&'a Result<T, E>> for InspectOkFn<F>
+where
+    F: FnOnce1<&'a T, Output = ()>,
+{
+    type Output = ();
+    fn call_once(self, arg: &'a Result<T, E>) {
+        if let Ok(x) = arg {
+            self.0.call_once(x)
+        }
+    }
+}
+
+pub(crate) fn inspect_ok_fn<F>(f: F) -> InspectOkFn<F> {
+    InspectOkFn(f)
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+pub struct InspectErrFn<F>(F);
+
+impl<'a, F, T, E> FnOnce1<&'a Result<T, E>> for InspectErrFn<F>
+where
+    F: FnOnce1<&'a E, Output = ()>,
+{
+    type Output = ();
+    fn call_once(self, arg: &'a Result<T, E>) {
+        if let Err(x) = arg {
+            self.0.call_once(x)
+        }
+    }
+}
+
+pub(crate) fn inspect_err_fn<F>(f: F) -> InspectErrFn<F> {
+    InspectErrFn(f)
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+pub struct UnwrapOrElseFn<F>(F);
+
+impl<F, T, E> FnOnce1<Result<T, E>> for UnwrapOrElseFn<F>
+where
+    F: FnOnce1<E, Output = T>,
+{
+    type Output = T;
+    fn call_once(self, arg: Result<T, E>) -> T {
+        arg.unwrap_or_else(|x| self.0.call_once(x))
+    }
+}
+
+pub(crate) fn unwrap_or_else_fn<F>(f: F) -> UnwrapOrElseFn<F> {
+    UnwrapOrElseFn(f)
+}
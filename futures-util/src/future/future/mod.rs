@@ -0,0 +1,309 @@
+//! Definition of the `FutureExt` combinator trait, which adds adapters to
+//! every future.
+
+use core::pin::{pin, Pin};
+use futures_core::future::{FusedFuture, Future};
+use futures_core::task::{Context, Poll, Waker};
+use pin_project_lite::pin_project;
+
+use crate::fns::{inspect_fn, FnOnce1, InspectFn};
+
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+#[cfg(feature = "alloc")]
+use futures_core::future::{BoxFuture, LocalBoxFuture};
+
+mod flatten;
+pub use self::flatten::Flatten;
+
+mod fuse;
+pub use self::fuse::Fuse;
+
+mod map;
+pub use self::map::Map;
+
+#[cfg(feature = "std")]
+mod catch_unwind;
+#[cfg(feature = "std")]
+pub use self::catch_unwind::CatchUnwind;
+
+#[cfg(feature = "std")]
+mod remote_handle;
+#[cfg(feature = "std")]
+pub use self::remote_handle::{Remote, RemoteHandle};
+
+pin_project! {
+    /// Future for the [`then`](FutureExt::then) method.
+    #[derive(Debug)]
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct Then<Fut1, Fut2, F> {
+        #[pin]
+        inner: Flatten<Map<Fut1, F>, Fut2>,
+    }
+}
+
+impl<Fut1, Fut2, F> Future for Then<Fut1, Fut2, F>
+where
+    Fut1: Future,
+    Fut2: Future,
+    F: FnOnce1<Fut1::Output, Output = Fut2>,
+{
+    type Output = Fut2::Output;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.project().inner.poll(ctx)
+    }
+}
+
+impl<Fut1, Fut2, F> FusedFuture for Then<Fut1, Fut2, F>
+where
+    Fut1: Future,
+    Fut2: Future,
+    F: FnOnce1<Fut1::Output, Output = Fut2>,
+{
+    fn is_terminated(&self) -> bool {
+        self.inner.is_terminated()
+    }
+}
+
+pin_project! {
+    /// Future for the [`inspect`](FutureExt::inspect) method.
+    #[derive(Debug)]
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct Inspect<Fut, F> {
+        #[pin]
+        inner: Map<Fut, InspectFn<F>>,
+    }
+}
+
+impl<Fut, F> Future for Inspect<Fut, F>
+where
+    Fut: Future,
+    F: FnOnce(&Fut::Output),
+{
+    type Output = Fut::Output;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.project().inner.poll(ctx)
+    }
+}
+
+impl<Fut, F> FusedFuture for Inspect<Fut, F>
+where
+    Fut: Future,
+    F: FnOnce(&Fut::Output),
+{
+    fn is_terminated(&self) -> bool {
+        self.inner.is_terminated()
+    }
+}
+
+impl<T: ?Sized> FutureExt for T where T: Future {}
+
+/// An extension trait for `Future`s that provides a variety of convenient
+/// adapters.
+pub trait FutureExt: Future {
+    /// Map this future's output to a different type, returning a new future of
+    /// the resulting type.
+    fn map<U, F>(self, f: F) -> Map<Self, F>
+    where
+        F: FnOnce(Self::Output) -> U,
+        Self: Sized,
+    {
+        Map::new(self, f)
+    }
+
+    /// Chain on a computation for when a future finished, passing the result
+    /// of the future to the provided closure `f`.
+    ///
+    /// The returned value of the closure must implement the `Future` trait and
+    /// is polled to completion in place of the original future.
+    fn then<Fut, F>(self, f: F) -> Then<Self, Fut, F>
+    where
+        F: FnOnce(Self::Output) -> Fut,
+        Fut: Future,
+        Self: Sized,
+    {
+        Then {
+            inner: Flatten::new(Map::new(self, f)),
+        }
+    }
+
+    /// Flatten the execution of this future when the output of this future is
+    /// itself another future.
+    fn flatten(self) -> Flatten<Self, Self::Output>
+    where
+        Self::Output: Future,
+        Self: Sized,
+    {
+        Flatten::new(self)
+    }
+
+    /// Do something with the output of a future before passing it on.
+    fn inspect<F>(self, f: F) -> Inspect<Self, F>
+    where
+        F: FnOnce(&Self::Output),
+        Self: Sized,
+    {
+        Inspect {
+            inner: Map::new(self, inspect_fn(f)),
+        }
+    }
+
+    /// Fuse a future such that `poll` will never again be called once it has
+    /// completed.
+    ///
+    /// The returned future is a [`FusedFuture`]: it reports when it has
+    /// terminated, and polling it afterwards returns `Poll::Pending` instead of
+    /// invoking unspecified behavior in the underlying future.
+    fn fuse(self) -> Fuse<Self>
+    where
+        Self: Sized,
+    {
+        Fuse::new(self)
+    }
+
+    /// Catches unwinding panics while polling the future.
+    ///
+    /// In general, panics within a future are propagated all the way up to
+    /// the task level. This combinator makes it possible to halt unwinding
+    /// within the future itself. The panic payload is returned as the error.
+    #[cfg(feature = "std")]
+    fn catch_unwind(self) -> CatchUnwind<Self>
+    where
+        Self: Sized + std::panic::UnwindSafe,
+    {
+        CatchUnwind::new(self)
+    }
+
+    /// Turn this future into a future that yields `()` on completion and
+    /// sends its output to another future on a separate task.
+    ///
+    /// This is mostly useful for spawning: the [`Remote`] half is spawned,
+    /// and the [`RemoteHandle`] half is awaited for the output. Dropping the
+    /// handle cancels the remote future, unless the handle was detached with
+    /// [`RemoteHandle::forget`].
+    ///
+    /// A panic in the remote future is caught and resumed when the handle is
+    /// polled.
+    #[cfg(feature = "std")]
+    fn remote_handle(self) -> (Remote<Self>, RemoteHandle<Self::Output>)
+    where
+        Self: Sized,
+    {
+        remote_handle::remote_handle(self)
+    }
+
+    /// Wrap the future in a Box, pinning it.
+    #[cfg(feature = "alloc")]
+    fn boxed<'a>(self) -> BoxFuture<'a, Self::Output>
+    where
+        Self: Sized + Send + 'a,
+    {
+        Box::pin(self)
+    }
+
+    /// Wrap the future in a Box, pinning it.
+    ///
+    /// Similar to `boxed`, but without the `Send` requirement.
+    #[cfg(feature = "alloc")]
+    fn boxed_local<'a>(self) -> LocalBoxFuture<'a, Self::Output>
+    where
+        Self: Sized + 'a,
+    {
+        Box::pin(self)
+    }
+
+    /// A convenience for calling `Future::poll` on `Unpin` future types.
+    fn poll_unpin(&mut self, ctx: &mut Context<'_>) -> Poll<Self::Output>
+    where
+        Self: Unpin,
+    {
+        Pin::new(self).poll(ctx)
+    }
+
+    /// Evaluates and consumes the future, returning the resulting output if
+    /// the future is ready after the first call to `Future::poll`.
+    ///
+    /// If `poll` instead returns `Poll::Pending`, `None` is returned.
+    fn now_or_never(self) -> Option<Self::Output>
+    where
+        Self: Sized,
+    {
+        let mut ctx = Context::from_waker(Waker::noop());
+
+        let this = pin!(self);
+        match this.poll(&mut ctx) {
+            Poll::Ready(x) => Some(x),
+            Poll::Pending => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FutureExt;
+    use core::cell::Cell;
+    use core::future::{pending, ready};
+    use core::task::Poll;
+    use futures_core::future::FusedFuture;
+
+    #[test]
+    fn map_then_inspect() {
+        let seen = Cell::new(0);
+        let fut = ready(1)
+            .map(|x| x + 1)
+            .inspect(|x| seen.set(*x))
+            .then(|x| ready(x * 10));
+        assert_eq!(fut.now_or_never(), Some(20));
+        assert_eq!(seen.get(), 2);
+    }
+
+    #[test]
+    fn map_is_terminated_after_completion() {
+        let mut fut = ready(1).map(|x| x + 1);
+        assert!(!fut.is_terminated());
+        let mut ctx = core::task::Context::from_waker(core::task::Waker::noop());
+        assert_eq!(fut.poll_unpin(&mut ctx), Poll::Ready(2));
+        assert!(fut.is_terminated());
+    }
+
+    #[test]
+    fn fuse_stops_polling() {
+        let mut ctx = core::task::Context::from_waker(core::task::Waker::noop());
+        let mut polls = 0;
+        let mut fut = core::future::poll_fn(|_| {
+            polls += 1;
+            Poll::Ready(())
+        })
+        .fuse();
+        assert_eq!(fut.poll_unpin(&mut ctx), Poll::Ready(()));
+        assert!(fut.is_terminated());
+        assert_eq!(fut.poll_unpin(&mut ctx), Poll::Pending);
+        drop(fut);
+        assert_eq!(polls, 1);
+    }
+
+    #[test]
+    fn now_or_never_pending() {
+        assert_eq!(pending::<()>().now_or_never(), None);
+    }
+
+    #[test]
+    fn boxed() {
+        let fut = ready(3).map(|x| x * 2).boxed();
+        assert_eq!(fut.now_or_never(), Some(6));
+        let fut = ready(std::rc::Rc::new(1)).boxed_local();
+        assert_eq!(*fut.now_or_never().unwrap(), 1);
+    }
+
+    #[test]
+    fn catch_unwind() {
+        let fut = ready(()).map(|()| panic!("boom")).catch_unwind();
+        let err = fut.now_or_never().unwrap().unwrap_err();
+        assert_eq!(err.downcast_ref::<&str>(), Some(&"boom"));
+
+        let fut = ready(1).catch_unwind();
+        assert_eq!(fut.now_or_never().unwrap().ok(), Some(1));
+    }
+}
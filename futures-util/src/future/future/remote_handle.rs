@@ -0,0 +1,112 @@
+use core::any::Any;
+use core::fmt;
+use core::pin::Pin;
+use core::sync::atomic::{AtomicBool, Ordering};
+use std::boxed::Box;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+use std::thread;
+
+use futures_channel::oneshot::{self, Receiver, Sender};
+use futures_core::future::Future;
+use futures_core::ready;
+use futures_core::task::{Context, Poll};
+use pin_project_lite::pin_project;
+
+use super::{CatchUnwind, FutureExt};
+
+/// The handle to a remote future returned by
+/// [`remote_handle`](super::FutureExt::remote_handle).
+///
+/// Awaiting the handle resolves to the remote future's output, and resumes
+/// its panic if it panicked.
+///
+/// Dropping the handle cancels the remote future the next time it is polled,
+/// unless [`forget`](RemoteHandle::forget) was called to detach it.
+#[must_use = "dropping a remote handle cancels the underlying future"]
+#[derive(Debug)]
+pub struct RemoteHandle<T> {
+    rx: Receiver<thread::Result<T>>,
+    keep_running: Arc<AtomicBool>,
+}
+
+impl<T> RemoteHandle<T> {
+    /// Drops this handle *without* canceling the underlying future.
+    ///
+    /// The remote future keeps running to completion and its output is
+    /// discarded.
+    pub fn forget(self) {
+        self.keep_running.store(true, Ordering::SeqCst);
+    }
+}
+
+impl<T: 'static> Future for RemoteHandle<T> {
+    type Output = T;
+
+    fn poll(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<T> {
+        match ready!(self.rx.poll_unpin(ctx)) {
+            Ok(Ok(output)) => Poll::Ready(output),
+            // The remote future panicked.
+            Ok(Err(e)) => panic::resume_unwind(e),
+            // The remote future was dropped before completing.
+            Err(e) => panic::resume_unwind(Box::new(e)),
+        }
+    }
+}
+
+type SendMsg<Fut> = Result<<Fut as Future>::Output, Box<dyn Any + Send + 'static>>;
+
+pin_project! {
+    /// A future which sends its output to the corresponding `RemoteHandle`.
+    /// Created by [`remote_handle`](super::FutureExt::remote_handle).
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct Remote<Fut: Future> {
+        tx: Option<Sender<SendMsg<Fut>>>,
+        keep_running: Arc<AtomicBool>,
+        #[pin]
+        future: CatchUnwind<AssertUnwindSafe<Fut>>,
+    }
+}
+
+impl<Fut: Future + fmt::Debug> fmt::Debug for Remote<Fut> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Remote").field(&self.future).finish()
+    }
+}
+
+impl<Fut: Future> Future for Remote<Fut> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<()> {
+        let this = self.project();
+
+        if this.tx.as_mut().unwrap().poll_canceled(ctx).is_ready()
+            && !this.keep_running.load(Ordering::SeqCst)
+        {
+            // The handle was dropped without being detached.
+            return Poll::Ready(());
+        }
+
+        let output = ready!(this.future.poll(ctx));
+
+        // The handle may have been detached, in which case nobody is
+        // listening for the output.
+        drop(this.tx.take().unwrap().send(output));
+        Poll::Ready(())
+    }
+}
+
+pub(super) fn remote_handle<Fut: Future>(future: Fut) -> (Remote<Fut>, RemoteHandle<Fut::Output>) {
+    let (tx, rx) = oneshot::channel();
+    let keep_running = Arc::new(AtomicBool::new(false));
+
+    // A panic in the remote future is carried to the handle and resumed
+    // there, so the handle observes it like any other panic.
+    let wrapped = Remote {
+        future: AssertUnwindSafe(future).catch_unwind(),
+        tx: Some(tx),
+        keep_running: keep_running.clone(),
+    };
+
+    (wrapped, RemoteHandle { rx, keep_running })
+}
@@ -0,0 +1,25 @@
+//! Asynchronous values.
+//!
+//! This module contains the [`FutureExt`] trait, which adds adapters to every
+//! [`Future`], the [`TryFutureExt`] trait for futures that resolve to a
+//! `Result`, and the future types those adapters return.
+
+#[doc(no_inline)]
+pub use futures_core::future::{FusedFuture, Future, TryFuture};
+
+#[cfg(feature = "alloc")]
+#[doc(no_inline)]
+pub use futures_core::future::{BoxFuture, LocalBoxFuture};
+
+#[allow(clippy::module_inception)]
+mod future;
+pub use self::future::{Flatten, Fuse, FutureExt, Inspect, Map, Then};
+
+#[cfg(feature = "std")]
+pub use self::future::{CatchUnwind, Remote, RemoteHandle};
+
+mod try_future;
+pub use self::try_future::{
+    AndThen, ErrInto, InspectErr, InspectOk, IntoFuture, MapErr, MapOk, OrElse, TryFlatten,
+    TryFlattenErr, TryFutureExt, UnwrapOrElse,
+};
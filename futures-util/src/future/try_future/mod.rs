@@ -0,0 +1,332 @@
+//! Definition of the `TryFutureExt` combinator trait, which adds adapters to
+//! every future that resolves to a `Result`.
+
+use core::pin::Pin;
+use futures_core::future::{FusedFuture, Future, TryFuture};
+use futures_core::task::{Context, Poll};
+use pin_project_lite::pin_project;
+
+use crate::fns::{
+    inspect_err_fn, inspect_fn, inspect_ok_fn, into_fn, map_err_fn, map_ok_fn, unwrap_or_else_fn,
+    InspectErrFn, InspectFn, InspectOkFn, IntoFn, MapErrFn, MapOkFn, UnwrapOrElseFn,
+};
+use crate::future::Map;
+
+mod into_future;
+pub use self::into_future::IntoFuture;
+
+mod try_flatten;
+pub use self::try_flatten::TryFlatten;
+
+mod try_flatten_err;
+pub use self::try_flatten_err::TryFlattenErr;
+
+// Every adapter below is a thin wrapper around a composition of the shared
+// adapters, so that the composed type doesn't leak into the public API.
+macro_rules! delegate_future {
+    ($(#[$attr:meta])* $name:ident<$($param:ident),*>($inner:ty)) => {
+        pin_project! {
+            $(#[$attr])*
+            #[derive(Debug)]
+            #[must_use = "futures do nothing unless you `.await` or poll them"]
+            pub struct $name<$($param),*> {
+                #[pin]
+                inner: $inner,
+            }
+        }
+
+        impl<$($param),*> Future for $name<$($param),*>
+        where
+            $inner: Future,
+        {
+            type Output = <$inner as Future>::Output;
+
+            #[inline]
+            fn poll(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
+                self.project().inner.poll(ctx)
+            }
+        }
+
+        impl<$($param),*> FusedFuture for $name<$($param),*>
+        where
+            $inner: FusedFuture,
+        {
+            fn is_terminated(&self) -> bool {
+                self.inner.is_terminated()
+            }
+        }
+    };
+}
+
+delegate_future!(
+    /// Future for the [`map_ok`](TryFutureExt::map_ok) method.
+    MapOk<Fut, F>(Map<IntoFuture<Fut>, MapOkFn<F>>)
+);
+
+delegate_future!(
+    /// Future for the [`map_err`](TryFutureExt::map_err) method.
+    MapErr<Fut, F>(Map<IntoFuture<Fut>, MapErrFn<F>>)
+);
+
+delegate_future!(
+    /// Future for the [`err_into`](TryFutureExt::err_into) method.
+    ErrInto<Fut, E>(MapErr<Fut, IntoFn<E>>)
+);
+
+delegate_future!(
+    /// Future for the [`and_then`](TryFutureExt::and_then) method.
+    AndThen<Fut1, Fut2, F>(TryFlatten<MapOk<Fut1, F>, Fut2>)
+);
+
+delegate_future!(
+    /// Future for the [`or_else`](TryFutureExt::or_else) method.
+    OrElse<Fut1, Fut2, F>(TryFlattenErr<MapErr<Fut1, F>, Fut2>)
+);
+
+delegate_future!(
+    /// Future for the [`inspect_ok`](TryFutureExt::inspect_ok) method.
+    InspectOk<Fut, F>(Map<IntoFuture<Fut>, InspectFn<InspectOkFn<F>>>)
+);
+
+delegate_future!(
+    /// Future for the [`inspect_err`](TryFutureExt::inspect_err) method.
+    InspectErr<Fut, F>(Map<IntoFuture<Fut>, InspectFn<InspectErrFn<F>>>)
+);
+
+delegate_future!(
+    /// Future for the [`unwrap_or_else`](TryFutureExt::unwrap_or_else) method.
+    UnwrapOrElse<Fut, F>(Map<IntoFuture<Fut>, UnwrapOrElseFn<F>>)
+);
+
+impl<Fut: ?Sized + TryFuture> TryFutureExt for Fut {}
+
+/// Adapters specific to [`Result`]-returning futures.
+///
+/// Every adapter drives the underlying future through
+/// [`TryFuture::try_poll`], so they apply to any future whose output is a
+/// `Result`.
+pub trait TryFutureExt: TryFuture {
+    /// Maps this future's success value to a different value.
+    ///
+    /// An error is passed through untouched.
+    fn map_ok<T, F>(self, f: F) -> MapOk<Self, F>
+    where
+        F: FnOnce(Self::Ok) -> T,
+        Self: Sized,
+    {
+        MapOk {
+            inner: Map::new(IntoFuture::new(self), map_ok_fn(f)),
+        }
+    }
+
+    /// Maps this future's error value to a different value.
+    ///
+    /// A success value is passed through untouched.
+    fn map_err<E, F>(self, f: F) -> MapErr<Self, F>
+    where
+        F: FnOnce(Self::Error) -> E,
+        Self: Sized,
+    {
+        MapErr {
+            inner: Map::new(IntoFuture::new(self), map_err_fn(f)),
+        }
+    }
+
+    /// Maps this future's error to any error type implementing
+    /// `From<Self::Error>`, the way `?` would.
+    fn err_into<E>(self) -> ErrInto<Self, E>
+    where
+        Self: Sized,
+        Self::Error: Into<E>,
+    {
+        ErrInto {
+            inner: MapErr {
+                inner: Map::new(IntoFuture::new(self), map_err_fn(into_fn())),
+            },
+        }
+    }
+
+    /// Executes another future after this one resolves successfully, passing
+    /// the success value to the closure `f` to produce it.
+    ///
+    /// An error from this future short-circuits, and `f` is never called.
+    fn and_then<Fut, F>(self, f: F) -> AndThen<Self, Fut, F>
+    where
+        F: FnOnce(Self::Ok) -> Fut,
+        Fut: TryFuture<Error = Self::Error>,
+        Self: Sized,
+    {
+        AndThen {
+            inner: TryFlatten::new(self.map_ok(f)),
+        }
+    }
+
+    /// Executes another future if this one resolves to an error, passing the
+    /// error to the closure `f` to produce it.
+    ///
+    /// A success value short-circuits, and `f` is never called.
+    fn or_else<Fut, F>(self, f: F) -> OrElse<Self, Fut, F>
+    where
+        F: FnOnce(Self::Error) -> Fut,
+        Fut: TryFuture<Ok = Self::Ok>,
+        Self: Sized,
+    {
+        OrElse {
+            inner: TryFlattenErr::new(self.map_err(f)),
+        }
+    }
+
+    /// Do something with the success value of a future before passing it on.
+    fn inspect_ok<F>(self, f: F) -> InspectOk<Self, F>
+    where
+        F: FnOnce(&Self::Ok),
+        Self: Sized,
+    {
+        InspectOk {
+            inner: Map::new(IntoFuture::new(self), inspect_fn(inspect_ok_fn(f))),
+        }
+    }
+
+    /// Do something with the error value of a future before passing it on.
+    fn inspect_err<F>(self, f: F) -> InspectErr<Self, F>
+    where
+        F: FnOnce(&Self::Error),
+        Self: Sized,
+    {
+        InspectErr {
+            inner: Map::new(IntoFuture::new(self), inspect_fn(inspect_err_fn(f))),
+        }
+    }
+
+    /// Flattens the execution of this future when the successful result of
+    /// this future is another future with the same error type.
+    fn try_flatten(self) -> TryFlatten<Self, Self::Ok>
+    where
+        Self::Ok: TryFuture<Error = Self::Error>,
+        Self: Sized,
+    {
+        TryFlatten::new(self)
+    }
+
+    /// Unwraps this future's output, producing a future with this future's
+    /// success type as its output, and using `f` to recover from an error.
+    fn unwrap_or_else<F>(self, f: F) -> UnwrapOrElse<Self, F>
+    where
+        F: FnOnce(Self::Error) -> Self::Ok,
+        Self: Sized,
+    {
+        UnwrapOrElse {
+            inner: Map::new(IntoFuture::new(self), unwrap_or_else_fn(f)),
+        }
+    }
+
+    /// Wraps a [`TryFuture`] into a type that implements [`Future`].
+    ///
+    /// This is useful to hand a generic `TryFuture` to an API that expects a
+    /// `Future`.
+    fn into_future(self) -> IntoFuture<Self>
+    where
+        Self: Sized,
+    {
+        IntoFuture::new(self)
+    }
+
+    /// A convenience method for calling [`TryFuture::try_poll`] on `Unpin`
+    /// future types.
+    fn try_poll_unpin(&mut self, ctx: &mut Context<'_>) -> Poll<Result<Self::Ok, Self::Error>>
+    where
+        Self: Unpin,
+    {
+        Pin::new(self).try_poll(ctx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TryFutureExt;
+    use crate::future::FutureExt;
+    use core::cell::Cell;
+    use core::future::ready;
+    use futures_core::future::FusedFuture;
+
+    #[derive(Debug, PartialEq)]
+    struct Wide(u8);
+
+    impl From<u8> for Wide {
+        fn from(x: u8) -> Self {
+            Wide(x)
+        }
+    }
+
+    #[test]
+    fn map_ok_and_map_err() {
+        let fut = ready(Ok::<u8, u8>(1)).map_ok(|x| x + 1).map_err(|e| e * 2);
+        assert_eq!(fut.now_or_never(), Some(Ok(2)));
+        let fut = ready(Err::<u8, u8>(3)).map_ok(|x| x + 1).map_err(|e| e * 2);
+        assert_eq!(fut.now_or_never(), Some(Err(6)));
+    }
+
+    #[test]
+    fn err_into() {
+        let fut = ready(Err::<(), u8>(4)).err_into::<Wide>();
+        assert_eq!(fut.now_or_never(), Some(Err(Wide(4))));
+    }
+
+    #[test]
+    fn and_then_short_circuits() {
+        let called = Cell::new(false);
+        let fut = ready(Err::<u8, u8>(1)).and_then(|x| {
+            called.set(true);
+            ready(Ok(x))
+        });
+        assert_eq!(fut.now_or_never(), Some(Err(1)));
+        assert!(!called.get());
+
+        let fut = ready(Ok::<u8, u8>(1)).and_then(|x| ready(Err::<u8, u8>(x + 1)));
+        assert_eq!(fut.now_or_never(), Some(Err(2)));
+    }
+
+    #[test]
+    fn or_else_recovers() {
+        let fut = ready(Err::<u8, u8>(1)).or_else(|e| ready(Ok::<u8, ()>(e + 10)));
+        assert_eq!(fut.now_or_never(), Some(Ok(11)));
+        let fut = ready(Ok::<u8, u8>(1)).or_else(|_| ready(Err::<u8, ()>(())));
+        assert_eq!(fut.now_or_never(), Some(Ok(1)));
+    }
+
+    #[test]
+    fn inspect_ok_and_err() {
+        let ok = Cell::new(0);
+        let err = Cell::new(0);
+        let fut = ready(Ok::<u8, u8>(5))
+            .inspect_ok(|x| ok.set(*x))
+            .inspect_err(|e| err.set(*e));
+        assert_eq!(fut.now_or_never(), Some(Ok(5)));
+        let fut = ready(Err::<u8, u8>(7))
+            .inspect_ok(|x| ok.set(*x + 100))
+            .inspect_err(|e| err.set(*e));
+        assert_eq!(fut.now_or_never(), Some(Err(7)));
+        assert_eq!((ok.get(), err.get()), (5, 7));
+    }
+
+    #[test]
+    fn try_flatten_and_unwrap_or_else() {
+        let fut = ready(Ok::<_, u8>(ready(Ok::<u8, u8>(3)))).try_flatten();
+        assert_eq!(fut.now_or_never(), Some(Ok(3)));
+        let fut = ready(Err::<u8, u8>(2)).unwrap_or_else(|e| e * 3);
+        assert_eq!(fut.now_or_never(), Some(6));
+    }
+
+    #[test]
+    fn terminated_after_completion() {
+        let mut ctx = core::task::Context::from_waker(core::task::Waker::noop());
+        let mut fut = ready(Ok::<u8, u8>(1)).and_then(|x| ready(Ok(x)));
+        assert!(!fut.is_terminated());
+        assert!(fut.try_poll_unpin(&mut ctx).is_ready());
+        assert!(fut.is_terminated());
+
+        let mut fut = ready(Ok::<u8, u8>(1)).into_future().fuse();
+        assert!(fut.poll_unpin(&mut ctx).is_ready());
+        assert!(fut.is_terminated());
+    }
+}
@@ -0,0 +1,302 @@
+use super::DEFAULT_BUF_SIZE;
+use core::fmt;
+use core::pin::Pin;
+use futures_core::ready;
+use futures_core::task::{Context, Poll};
+use futures_io::{AsyncBufRead, AsyncRead, AsyncSeek, IoSliceMut, SeekFrom};
+use pin_project_lite::pin_project;
+use std::boxed::Box;
+use std::io::{self, Read};
+use std::vec;
+
+pin_project! {
+    /// Adds buffering to a reader.
+    ///
+    /// A `BufReader` reads large chunks from the underlying [`AsyncRead`] and
+    /// serves small reads from memory, and implements [`AsyncBufRead`]. Any
+    /// buffered data is lost when the `BufReader` is dropped.
+    pub struct BufReader<R> {
+        #[pin]
+        inner: R,
+        buffer: Box<[u8]>,
+        pos: usize,
+        cap: usize,
+    }
+}
+
+impl<R: AsyncRead> BufReader<R> {
+    /// Creates a new `BufReader` with a buffer of 8 KiB.
+    pub fn new(inner: R) -> Self {
+        Self::with_capacity(DEFAULT_BUF_SIZE, inner)
+    }
+
+    /// Creates a new `BufReader` with a buffer of `capacity` bytes.
+    pub fn with_capacity(capacity: usize, inner: R) -> Self {
+        Self {
+            inner,
+            buffer: vec![0; capacity].into_boxed_slice(),
+            pos: 0,
+            cap: 0,
+        }
+    }
+}
+
+impl<R> BufReader<R> {
+    /// Returns a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying reader.
+    ///
+    /// Reading from it directly skips over the buffered data.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Returns a pinned mutable reference to the underlying reader.
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut R> {
+        self.project().inner
+    }
+
+    /// Consumes the `BufReader`, returning the underlying reader.
+    ///
+    /// Any buffered data is lost.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Returns the buffered data, without reading more.
+    pub fn buffer(&self) -> &[u8] {
+        &self.buffer[self.pos..self.cap]
+    }
+
+    /// Returns the size of the buffer.
+    pub fn capacity(&self) -> usize {
+        self.buffer.len()
+    }
+
+    fn discard_buffer(self: Pin<&mut Self>) {
+        let this = self.project();
+        *this.pos = 0;
+        *this.cap = 0;
+    }
+}
+
+impl<R: AsyncRead + AsyncSeek> BufReader<R> {
+    /// Seeks `offset` bytes from the current position, keeping the buffer
+    /// if the new position lies within it.
+    ///
+    /// Unlike [`poll_seek`](AsyncSeek::poll_seek), this doesn't report the
+    /// new position.
+    pub fn poll_seek_relative(
+        mut self: Pin<&mut Self>,
+        ctx: &mut Context<'_>,
+        offset: i64,
+    ) -> Poll<io::Result<()>> {
+        let pos = self.pos as i64;
+        if let Some(new_pos) = pos.checked_add(offset) {
+            if (0..=self.cap as i64).contains(&new_pos) {
+                *self.project().pos = new_pos as usize;
+                return Poll::Ready(Ok(()));
+            }
+        }
+        ready!(self.as_mut().poll_seek(ctx, SeekFrom::Current(offset)))?;
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<R: AsyncRead> AsyncRead for BufReader<R> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        ctx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        // Reads at least as large as the buffer skip it when it's empty.
+        if self.pos == self.cap && buf.len() >= self.buffer.len() {
+            let res = ready!(self.as_mut().project().inner.poll_read(ctx, buf));
+            self.discard_buffer();
+            return Poll::Ready(res);
+        }
+        let mut rem = ready!(self.as_mut().poll_fill_buf(ctx))?;
+        let n = rem.read(buf)?;
+        self.consume(n);
+        Poll::Ready(Ok(n))
+    }
+
+    fn poll_read_vectored(
+        mut self: Pin<&mut Self>,
+        ctx: &mut Context<'_>,
+        bufs: &mut [IoSliceMut<'_>],
+    ) -> Poll<io::Result<usize>> {
+        let total_len = bufs.iter().map(|b| b.len()).sum::<usize>();
+        if self.pos == self.cap && total_len >= self.buffer.len() {
+            let res = ready!(self.as_mut().project().inner.poll_read_vectored(ctx, bufs));
+            self.discard_buffer();
+            return Poll::Ready(res);
+        }
+        let mut rem = ready!(self.as_mut().poll_fill_buf(ctx))?;
+        let n = rem.read_vectored(bufs)?;
+        self.consume(n);
+        Poll::Ready(Ok(n))
+    }
+}
+
+impl<R: AsyncRead> AsyncBufRead for BufReader<R> {
+    fn poll_fill_buf(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        let this = self.project();
+        if *this.pos >= *this.cap {
+            *this.cap = ready!(this.inner.poll_read(ctx, this.buffer))?;
+            *this.pos = 0;
+        }
+        Poll::Ready(Ok(&this.buffer[*this.pos..*this.cap]))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        let this = self.project();
+        *this.pos = (*this.pos + amt).min(*this.cap);
+    }
+}
+
+impl<R: AsyncRead + AsyncSeek> AsyncSeek for BufReader<R> {
+    /// Seeks the underlying reader, discarding the buffer.
+    ///
+    /// `SeekFrom::Current` is relative to the position of the `BufReader`,
+    /// which lags behind the underlying reader by the buffered bytes. Use
+    /// [`BufReader::poll_seek_relative`] to keep the buffer instead.
+    fn poll_seek(
+        mut self: Pin<&mut Self>,
+        ctx: &mut Context<'_>,
+        pos: SeekFrom,
+    ) -> Poll<io::Result<u64>> {
+        let result = match pos {
+            SeekFrom::Current(n) => {
+                let remainder = (self.cap - self.pos) as i64;
+                match n.checked_sub(remainder) {
+                    Some(offset) => ready!(self
+                        .as_mut()
+                        .project()
+                        .inner
+                        .poll_seek(ctx, SeekFrom::Current(offset)))?,
+                    None => {
+                        // Seek back over the buffer first, then by `n`. If
+                        // the second seek is pending, the first one must not
+                        // be repeated, so the buffer is gone by then.
+                        ready!(self
+                            .as_mut()
+                            .project()
+                            .inner
+                            .poll_seek(ctx, SeekFrom::Current(-remainder)))?;
+                        self.as_mut().discard_buffer();
+                        ready!(self
+                            .as_mut()
+                            .project()
+                            .inner
+                            .poll_seek(ctx, SeekFrom::Current(n)))?
+                    }
+                }
+            }
+            _ => ready!(self.as_mut().project().inner.poll_seek(ctx, pos))?,
+        };
+        self.discard_buffer();
+        Poll::Ready(Ok(result))
+    }
+}
+
+impl<R: fmt::Debug> fmt::Debug for BufReader<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BufReader")
+            .field("reader", &self.inner)
+            .field(
+                "buffer",
+                &format_args!("{}/{}", self.cap - self.pos, self.buffer.len()),
+            )
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BufReader;
+    use crate::io::tests::{trickle, wait};
+    use crate::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncSeekExt, SeekFrom};
+    use core::future::poll_fn;
+    use core::pin::Pin;
+    use std::vec::Vec;
+
+    #[test]
+    fn fill_buf_and_consume() {
+        let mut reader = BufReader::with_capacity(4, trickle(b"abcdefghij", 3));
+        let buf = wait(poll_fn(|ctx| {
+            Pin::new(&mut reader)
+                .poll_fill_buf(ctx)
+                .map_ok(<[u8]>::to_vec)
+        }))
+        .unwrap();
+        assert_eq!(buf, b"abc");
+
+        // Without a `consume` the same bytes come back.
+        assert_eq!(reader.buffer(), b"abc");
+        Pin::new(&mut reader).consume(2);
+        assert_eq!(reader.buffer(), b"c");
+
+        let mut rest = Vec::new();
+        wait(reader.read_until(b'h', &mut rest)).unwrap();
+        assert_eq!(rest, b"cdefgh");
+        assert_eq!(reader.get_ref().pos, 9);
+    }
+
+    #[test]
+    fn large_reads_bypass_the_buffer() {
+        let mut reader = BufReader::with_capacity(2, &b"abcdef"[..]);
+        let mut buf = [0; 4];
+        assert_eq!(wait(reader.read(&mut buf)).unwrap(), 4);
+        assert_eq!(reader.buffer(), b"");
+        let mut buf = [0; 1];
+        assert_eq!(wait(reader.read(&mut buf)).unwrap(), 1);
+        assert_eq!(reader.buffer(), b"f");
+    }
+
+    #[test]
+    fn seeking_accounts_for_the_buffer() {
+        let mut reader = BufReader::with_capacity(4, trickle(b"0123456789", 10));
+        let mut byte = [0; 1];
+        wait(reader.read_exact(&mut byte)).unwrap();
+        assert_eq!(reader.buffer(), b"123");
+        assert_eq!(wait(reader.stream_position()).unwrap(), 1);
+        assert_eq!(reader.buffer(), b"");
+
+        wait(reader.read_exact(&mut byte)).unwrap();
+        assert_eq!(wait(reader.seek(SeekFrom::Current(-2))).unwrap(), 0);
+        assert_eq!(wait(reader.seek(SeekFrom::End(-1))).unwrap(), 9);
+        wait(reader.read_exact(&mut byte)).unwrap();
+        assert_eq!(&byte, b"9");
+    }
+
+    #[test]
+    fn seek_relative_keeps_the_buffer() {
+        let mut reader = BufReader::with_capacity(4, trickle(b"0123456789", 10));
+        let mut byte = [0; 1];
+        wait(reader.read_exact(&mut byte)).unwrap();
+
+        wait(poll_fn(|ctx| {
+            Pin::new(&mut reader).poll_seek_relative(ctx, 2)
+        }))
+        .unwrap();
+        assert_eq!(reader.buffer(), b"3");
+        wait(poll_fn(|ctx| {
+            Pin::new(&mut reader).poll_seek_relative(ctx, -3)
+        }))
+        .unwrap();
+        assert_eq!(reader.buffer(), b"0123");
+
+        // Outside of the buffer, it falls back to a real seek.
+        wait(poll_fn(|ctx| {
+            Pin::new(&mut reader).poll_seek_relative(ctx, 6)
+        }))
+        .unwrap();
+        assert_eq!(reader.buffer(), b"");
+        wait(reader.read_exact(&mut byte)).unwrap();
+        assert_eq!(&byte, b"6");
+    }
+}
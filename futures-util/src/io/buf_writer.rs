@@ -0,0 +1,240 @@
+use super::DEFAULT_BUF_SIZE;
+use core::fmt;
+use core::pin::Pin;
+use futures_core::ready;
+use futures_core::task::{Context, Poll};
+use futures_io::{AsyncSeek, AsyncWrite, IoSlice, SeekFrom};
+use pin_project_lite::pin_project;
+use std::io;
+use std::vec::Vec;
+
+pin_project! {
+    /// Adds buffering to a writer.
+    ///
+    /// A `BufWriter` collects small writes in memory and passes them on to
+    /// the underlying [`AsyncWrite`] in large batches, on
+    /// [`poll_flush`](AsyncWrite::poll_flush) and
+    /// [`poll_close`](AsyncWrite::poll_close) at the latest. Data that hasn't
+    /// been flushed is lost when the `BufWriter` is dropped.
+    pub struct BufWriter<W> {
+        #[pin]
+        inner: W,
+        buf: Vec<u8>,
+        written: usize,
+    }
+}
+
+impl<W: AsyncWrite> BufWriter<W> {
+    /// Creates a new `BufWriter` with a buffer of 8 KiB.
+    pub fn new(inner: W) -> Self {
+        Self::with_capacity(DEFAULT_BUF_SIZE, inner)
+    }
+
+    /// Creates a new `BufWriter` with a buffer of `capacity` bytes.
+    pub fn with_capacity(capacity: usize, inner: W) -> Self {
+        Self {
+            inner,
+            buf: Vec::with_capacity(capacity),
+            written: 0,
+        }
+    }
+
+    /// Writes the buffered data to the underlying writer, without flushing
+    /// it.
+    pub(super) fn flush_buf(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let mut this = self.project();
+        let mut ret = Ok(());
+        while *this.written < this.buf.len() {
+            match ready!(this
+                .inner
+                .as_mut()
+                .poll_write(ctx, &this.buf[*this.written..]))
+            {
+                Ok(0) => {
+                    ret = Err(io::Error::new(
+                        io::ErrorKind::WriteZero,
+                        "failed to write the buffered data",
+                    ));
+                    break;
+                }
+                Ok(n) => *this.written += n,
+                Err(e) => {
+                    ret = Err(e);
+                    break;
+                }
+            }
+        }
+        // Only drop what was written, so that errors can be retried.
+        this.buf.drain(..*this.written);
+        *this.written = 0;
+        Poll::Ready(ret)
+    }
+}
+
+impl<W> BufWriter<W> {
+    /// Returns a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying writer.
+    ///
+    /// Writing to it directly skips ahead of the buffered data.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Returns a pinned mutable reference to the underlying writer.
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut W> {
+        self.project().inner
+    }
+
+    /// Consumes the `BufWriter`, returning the underlying writer.
+    ///
+    /// Any buffered data is lost.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    /// Returns the buffered data.
+    pub fn buffer(&self) -> &[u8] {
+        &self.buf
+    }
+
+    /// Returns the size of the buffer.
+    pub fn capacity(&self) -> usize {
+        self.buf.capacity()
+    }
+
+    /// Buffers as much of `buf` as fits without growing the buffer,
+    /// returning the number of bytes taken.
+    pub(super) fn write_to_buf(self: Pin<&mut Self>, buf: &[u8]) -> usize {
+        let this = self.project();
+        let n = buf.len().min(this.buf.capacity() - this.buf.len());
+        this.buf.extend_from_slice(&buf[..n]);
+        n
+    }
+}
+
+impl<W: AsyncWrite> AsyncWrite for BufWriter<W> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        ctx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        if self.buf.len() + buf.len() > self.buf.capacity() {
+            ready!(self.as_mut().flush_buf(ctx))?;
+        }
+        if buf.len() >= self.buf.capacity() {
+            self.project().inner.poll_write(ctx, buf)
+        } else {
+            self.project().buf.extend_from_slice(buf);
+            Poll::Ready(Ok(buf.len()))
+        }
+    }
+
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        ctx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        let total_len = bufs.iter().map(|b| b.len()).sum::<usize>();
+        if self.buf.len() + total_len > self.buf.capacity() {
+            ready!(self.as_mut().flush_buf(ctx))?;
+        }
+        if total_len >= self.buf.capacity() {
+            self.project().inner.poll_write_vectored(ctx, bufs)
+        } else {
+            let this = self.project();
+            for buf in bufs {
+                this.buf.extend_from_slice(buf);
+            }
+            Poll::Ready(Ok(total_len))
+        }
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        ready!(self.as_mut().flush_buf(ctx))?;
+        self.project().inner.poll_flush(ctx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        ready!(self.as_mut().flush_buf(ctx))?;
+        self.project().inner.poll_close(ctx)
+    }
+}
+
+impl<W: AsyncWrite + AsyncSeek> AsyncSeek for BufWriter<W> {
+    /// Writes out the buffer, then seeks the underlying writer.
+    fn poll_seek(
+        mut self: Pin<&mut Self>,
+        ctx: &mut Context<'_>,
+        pos: SeekFrom,
+    ) -> Poll<io::Result<u64>> {
+        ready!(self.as_mut().flush_buf(ctx))?;
+        self.project().inner.poll_seek(ctx, pos)
+    }
+}
+
+impl<W: fmt::Debug> fmt::Debug for BufWriter<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BufWriter")
+            .field("writer", &self.inner)
+            .field(
+                "buffer",
+                &format_args!("{}/{}", self.buf.len(), self.buf.capacity()),
+            )
+            .field("written", &self.written)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BufWriter;
+    use crate::io::tests::{trickle, wait};
+    use crate::io::{AsyncSeekExt, AsyncWriteExt, IoSlice, SeekFrom};
+
+    #[test]
+    fn batches_small_writes() {
+        let mut writer = BufWriter::with_capacity(8, trickle(b"", 3));
+        assert_eq!(wait(writer.write(b"abc")).unwrap(), 3);
+        assert_eq!(wait(writer.write(b"defg")).unwrap(), 4);
+        assert_eq!(writer.buffer(), b"abcdefg");
+        assert_eq!(writer.get_ref().data, b"");
+
+        // Doesn't fit, so the buffer goes out first.
+        assert_eq!(wait(writer.write(b"hi")).unwrap(), 2);
+        assert_eq!(writer.get_ref().data, b"abcdefg");
+        assert_eq!(writer.buffer(), b"hi");
+
+        wait(writer.flush()).unwrap();
+        assert_eq!(writer.get_ref().data, b"abcdefghi");
+        assert_eq!(writer.buffer(), b"");
+    }
+
+    #[test]
+    fn large_writes_bypass_the_buffer() {
+        let mut writer = BufWriter::with_capacity(4, trickle(b"", 16));
+        wait(writer.write_all(b"ab")).unwrap();
+        assert_eq!(wait(writer.write(b"cdefgh")).unwrap(), 6);
+        assert_eq!(writer.buffer(), b"");
+        assert_eq!(writer.get_ref().data, b"abcdefgh");
+
+        let mut bufs = [IoSlice::new(b"ij"), IoSlice::new(b"k")];
+        wait(writer.write_all_vectored(&mut bufs)).unwrap();
+        assert_eq!(writer.buffer(), b"ijk");
+    }
+
+    #[test]
+    fn close_and_seek_flush() {
+        let mut writer = BufWriter::new(trickle(b"", 2));
+        wait(writer.write_all(b"hello")).unwrap();
+        assert_eq!(wait(writer.seek(SeekFrom::Start(1))).unwrap(), 1);
+        assert_eq!(writer.get_ref().data, b"hello");
+
+        wait(writer.write_all(b" world")).unwrap();
+        wait(writer.close()).unwrap();
+        assert_eq!(writer.into_inner().data, b"hello world");
+    }
+}
@@ -0,0 +1,306 @@
+use super::DEFAULT_BUF_SIZE;
+use core::fmt;
+use core::pin::Pin;
+use futures_core::future::Future;
+use futures_core::ready;
+use futures_core::task::{Context, Poll};
+use futures_io::{AsyncRead, AsyncWrite};
+use pin_project_lite::pin_project;
+use std::boxed::Box;
+use std::io;
+use std::vec;
+
+/// Copies everything from `reader` to `writer`, then closes `writer`.
+///
+/// Resolves to the number of bytes the returned future copied. Closing
+/// `writer` once `reader` reaches end-of-file passes the end of the stream
+/// on; for a socket, it shuts down the write half.
+///
+/// # Cancel safety
+///
+/// The data is copied through a buffer owned by the returned future, so
+/// dropping it loses whatever was read into the buffer but not yet written,
+/// at most 8 KiB. Copy with [`CopyBuffer::copy`] to be able to resume
+/// instead, or use [`copy_buf`](super::copy_buf) to lose nothing.
+///
+/// ```
+/// use futures_util::future::FutureExt;
+/// use futures_util::io::{copy, Cursor};
+///
+/// let mut writer = Cursor::new(Vec::new());
+/// let n = copy(&b"hello"[..], &mut writer).now_or_never().unwrap().unwrap();
+/// assert_eq!(n, 5);
+/// assert_eq!(writer.into_inner(), b"hello");
+/// ```
+pub fn copy<R, W>(reader: R, writer: &mut W) -> Copy<'_, R, W>
+where
+    R: AsyncRead,
+    W: AsyncWrite + Unpin + ?Sized,
+{
+    Copy::new(reader, writer, Buffer::Owned(CopyBuffer::new()))
+}
+
+pin_project! {
+    /// Future for the [`copy`] function and [`CopyBuffer::copy`].
+    #[derive(Debug)]
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct Copy<'a, R, W: ?Sized> {
+        #[pin]
+        reader: R,
+        writer: &'a mut W,
+        buf: Buffer<'a>,
+        start: u64,
+    }
+}
+
+impl<'a, R, W: ?Sized> Copy<'a, R, W> {
+    fn new(reader: R, writer: &'a mut W, mut buf: Buffer<'a>) -> Self {
+        let start = buf.get_mut().amt;
+        Self {
+            reader,
+            writer,
+            buf,
+            start,
+        }
+    }
+}
+
+impl<R, W> Future for Copy<'_, R, W>
+where
+    R: AsyncRead,
+    W: AsyncWrite + Unpin + ?Sized,
+{
+    type Output = io::Result<u64>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let start = *this.start;
+        this.buf
+            .get_mut()
+            .poll_copy(ctx, this.reader, Pin::new(&mut **this.writer))
+            .map_ok(|amt| amt - start)
+    }
+}
+
+/// The buffer of a copy future, which either owns it or borrows it from
+/// the caller.
+#[derive(Debug)]
+pub(super) enum Buffer<'a> {
+    Owned(CopyBuffer),
+    Borrowed(&'a mut CopyBuffer),
+}
+
+impl Buffer<'_> {
+    pub(super) fn get_mut(&mut self) -> &mut CopyBuffer {
+        match self {
+            Self::Owned(buf) => buf,
+            Self::Borrowed(buf) => buf,
+        }
+    }
+}
+
+/// The state of a copy in one direction, kept by the caller so that the
+/// copy can be resumed after its future was dropped.
+///
+/// [`CopyBuffer::copy`] and [`CopyBuffer::copy_bidirectional`] keep the
+/// bytes that were read but not written yet in the buffer. Copying again
+/// with the same buffer and streams picks up where the dropped future left
+/// off. A buffer belongs to one copy; once that completed,
+/// copying with it again resolves right away.
+///
+/// Like the future of [`copy`], each of these futures resolves to the
+/// number of bytes it copied itself; [`amount`](CopyBuffer::amount) is the
+/// total across all of them.
+///
+/// ```
+/// use futures_util::future::FutureExt;
+/// use futures_util::io::{CopyBuffer, Cursor};
+///
+/// let mut reader = &b"hello"[..];
+/// let mut writer = Cursor::new(Vec::new());
+/// let mut buf = CopyBuffer::new();
+/// let n = buf.copy(&mut reader, &mut writer).now_or_never().unwrap().unwrap();
+/// assert_eq!(n, 5);
+/// assert_eq!(buf.amount(), 5);
+/// assert_eq!(writer.into_inner(), b"hello");
+/// ```
+pub struct CopyBuffer {
+    buf: Box<[u8]>,
+    pos: usize,
+    cap: usize,
+    amt: u64,
+    read_done: bool,
+    need_flush: bool,
+    closed: bool,
+}
+
+impl CopyBuffer {
+    /// Creates a buffer of 8 KiB.
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_BUF_SIZE)
+    }
+
+    /// Creates a buffer of `capacity` bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is 0.
+    pub fn with_capacity(capacity: usize) -> Self {
+        assert!(capacity > 0, "copy buffer capacity must be non-zero");
+        Self {
+            buf: vec![0; capacity].into_boxed_slice(),
+            pos: 0,
+            cap: 0,
+            amt: 0,
+            read_done: false,
+            need_flush: false,
+            closed: false,
+        }
+    }
+
+    /// Returns the number of bytes copied through this buffer so far.
+    pub fn amount(&self) -> u64 {
+        self.amt
+    }
+
+    /// Like [`copy`], but through this buffer, so that a copy whose future
+    /// was dropped can be resumed by calling this again.
+    pub fn copy<'a, R, W>(
+        &'a mut self,
+        reader: &'a mut R,
+        writer: &'a mut W,
+    ) -> Copy<'a, &'a mut R, W>
+    where
+        R: AsyncRead + Unpin + ?Sized,
+        W: AsyncWrite + Unpin + ?Sized,
+    {
+        Copy::new(reader, writer, Buffer::Borrowed(self))
+    }
+
+    /// Copies from `reader` to `writer` until end-of-file, closes `writer`,
+    /// and resolves to the number of bytes copied. Once that happened, it
+    /// keeps resolving to the same number without touching either.
+    pub(super) fn poll_copy<R, W>(
+        &mut self,
+        ctx: &mut Context<'_>,
+        mut reader: Pin<&mut R>,
+        mut writer: Pin<&mut W>,
+    ) -> Poll<io::Result<u64>>
+    where
+        R: AsyncRead + ?Sized,
+        W: AsyncWrite + ?Sized,
+    {
+        loop {
+            if self.closed {
+                return Poll::Ready(Ok(self.amt));
+            }
+
+            if self.pos == self.cap && !self.read_done {
+                match reader.as_mut().poll_read(ctx, &mut self.buf) {
+                    Poll::Ready(Ok(0)) => self.read_done = true,
+                    Poll::Ready(Ok(n)) => {
+                        self.pos = 0;
+                        self.cap = n;
+                    }
+                    Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                    Poll::Pending => {
+                        // Don't leave data sitting in the writer while the
+                        // reader waits, the other side may wait for it.
+                        if self.need_flush {
+                            ready!(writer.as_mut().poll_flush(ctx))?;
+                            self.need_flush = false;
+                        }
+                        return Poll::Pending;
+                    }
+                }
+            }
+
+            while self.pos < self.cap {
+                let n = ready!(writer
+                    .as_mut()
+                    .poll_write(ctx, &self.buf[self.pos..self.cap]))?;
+                if n == 0 {
+                    return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+                }
+                self.pos += n;
+                self.amt += n as u64;
+                self.need_flush = true;
+            }
+
+            if self.read_done {
+                ready!(writer.as_mut().poll_close(ctx))?;
+                self.closed = true;
+            }
+        }
+    }
+}
+
+impl Default for CopyBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for CopyBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CopyBuffer")
+            .field("pending", &(self.cap - self.pos))
+            .field("capacity", &self.buf.len())
+            .field("amount", &self.amt)
+            .field("closed", &self.closed)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{copy, CopyBuffer};
+    use crate::future::FutureExt;
+    use crate::io::tests::{trickle, wait};
+    use crate::io::ErrorKind;
+    use std::vec::Vec;
+
+    #[test]
+    fn copies_and_closes() {
+        let data: Vec<u8> = (0..20_000).map(|i| i as u8).collect();
+        let mut writer = trickle(b"", 300);
+        assert_eq!(
+            wait(copy(trickle(&data, 1000), &mut writer)).unwrap(),
+            20_000
+        );
+        assert!(writer.data == data);
+        // `Trickle` stops accepting bytes once closed.
+        assert_eq!(writer.step, 0);
+    }
+
+    #[test]
+    fn resumes_after_being_dropped() {
+        let data: Vec<u8> = (0..5000).map(|i| i as u8).collect();
+        let mut reader = trickle(&data, 700);
+        let mut writer = trickle(b"", 100);
+        let mut buf = CopyBuffer::with_capacity(512);
+
+        // Each attempt is dropped after a few polls, some of them with bytes
+        // read but not written yet.
+        for _ in 0..10 {
+            let mut fut = buf.copy(&mut reader, &mut writer);
+            for _ in 0..5 {
+                let _ = (&mut fut).now_or_never();
+            }
+        }
+        let copied = buf.amount();
+        assert!(copied < 5000);
+        let rest = wait(buf.copy(&mut reader, &mut writer)).unwrap();
+        assert_eq!(copied + rest, 5000);
+        assert_eq!(buf.amount(), 5000);
+        assert!(writer.data == data);
+        assert_eq!(writer.step, 0);
+    }
+
+    #[test]
+    fn writer_that_stops_accepting_bytes() {
+        let mut writer = trickle(b"", 0);
+        let err = wait(copy(&b"data"[..], &mut writer)).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::WriteZero);
+    }
+}
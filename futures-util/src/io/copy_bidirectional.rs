@@ -0,0 +1,210 @@
+use super::copy::{Buffer, CopyBuffer};
+use core::pin::Pin;
+use futures_core::future::Future;
+use futures_core::ready;
+use futures_core::task::{Context, Poll};
+use futures_io::{AsyncRead, AsyncWrite};
+use std::io;
+
+/// Copies between `a` and `b` in both directions at once, as a proxy does.
+///
+/// When one side reaches end-of-file, the other side is closed, and the
+/// copy in the opposite direction carries on. Resolves to the number of
+/// bytes the returned future copied from `a` to `b` and from `b` to `a`
+/// once both directions are done, or to the first error in either
+/// direction.
+///
+/// # Cancel safety
+///
+/// As with [`copy`](super::copy), each direction is copied through a buffer
+/// owned by the returned future, so dropping it loses whatever was read
+/// but not yet written. Copy with [`CopyBuffer::copy_bidirectional`] to be
+/// able to resume instead.
+pub fn copy_bidirectional<'a, A, B>(a: &'a mut A, b: &'a mut B) -> CopyBidirectional<'a, A, B>
+where
+    A: AsyncRead + AsyncWrite + Unpin + ?Sized,
+    B: AsyncRead + AsyncWrite + Unpin + ?Sized,
+{
+    CopyBidirectional::new(
+        a,
+        b,
+        Buffer::Owned(CopyBuffer::new()),
+        Buffer::Owned(CopyBuffer::new()),
+    )
+}
+
+impl CopyBuffer {
+    /// Like [`copy_bidirectional`], but through `a_to_b` and `b_to_a`, so
+    /// that a copy whose future was dropped can be resumed by calling this
+    /// again.
+    pub fn copy_bidirectional<'a, A, B>(
+        a: &'a mut A,
+        b: &'a mut B,
+        a_to_b: &'a mut CopyBuffer,
+        b_to_a: &'a mut CopyBuffer,
+    ) -> CopyBidirectional<'a, A, B>
+    where
+        A: AsyncRead + AsyncWrite + Unpin + ?Sized,
+        B: AsyncRead + AsyncWrite + Unpin + ?Sized,
+    {
+        CopyBidirectional::new(a, b, Buffer::Borrowed(a_to_b), Buffer::Borrowed(b_to_a))
+    }
+}
+
+/// Future for the [`copy_bidirectional`] function and
+/// [`CopyBuffer::copy_bidirectional`].
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct CopyBidirectional<'a, A: ?Sized, B: ?Sized> {
+    a: &'a mut A,
+    b: &'a mut B,
+    a_to_b: Buffer<'a>,
+    b_to_a: Buffer<'a>,
+    start: (u64, u64),
+}
+
+impl<'a, A: ?Sized, B: ?Sized> CopyBidirectional<'a, A, B> {
+    fn new(a: &'a mut A, b: &'a mut B, mut a_to_b: Buffer<'a>, mut b_to_a: Buffer<'a>) -> Self {
+        let start = (a_to_b.get_mut().amount(), b_to_a.get_mut().amount());
+        Self {
+            a,
+            b,
+            a_to_b,
+            b_to_a,
+            start,
+        }
+    }
+}
+
+impl<A: ?Sized, B: ?Sized> Unpin for CopyBidirectional<'_, A, B> {}
+
+impl<A, B> Future for CopyBidirectional<'_, A, B>
+where
+    A: AsyncRead + AsyncWrite + Unpin + ?Sized,
+    B: AsyncRead + AsyncWrite + Unpin + ?Sized,
+{
+    type Output = io::Result<(u64, u64)>;
+
+    fn poll(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        // Both directions must be polled to register for wakeups; a finished
+        // direction keeps returning its count.
+        let a_to_b =
+            this.a_to_b
+                .get_mut()
+                .poll_copy(ctx, Pin::new(&mut *this.a), Pin::new(&mut *this.b))?;
+        let b_to_a =
+            this.b_to_a
+                .get_mut()
+                .poll_copy(ctx, Pin::new(&mut *this.b), Pin::new(&mut *this.a))?;
+        let a_to_b = ready!(a_to_b);
+        let b_to_a = ready!(b_to_a);
+        Poll::Ready(Ok((a_to_b - this.start.0, b_to_a - this.start.1)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::copy_bidirectional;
+    use crate::future::FutureExt;
+    use crate::io::tests::{trickle, wait, Trickle};
+    use crate::io::{duplex, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, CopyBuffer};
+    use core::pin::Pin;
+    use core::task::{Context, Poll};
+    use futures_executor::block_on;
+    use std::io;
+    use std::thread;
+    use std::vec::Vec;
+
+    #[test]
+    fn proxies_both_directions_with_half_close() {
+        let (mut client, mut a) = duplex(64);
+        let (mut b, mut server) = duplex(64);
+        let proxy = thread::spawn(move || block_on(copy_bidirectional(&mut a, &mut b)).unwrap());
+
+        // The server sees the end of the request, yet can still respond.
+        let server = thread::spawn(move || {
+            let mut request = Vec::new();
+            block_on(server.read_to_end(&mut request)).unwrap();
+            block_on(server.write_all(&[7; 10_000])).unwrap();
+            block_on(server.close()).unwrap();
+            request
+        });
+
+        block_on(client.write_all(b"ping")).unwrap();
+        block_on(client.close()).unwrap();
+        let mut response = Vec::new();
+        block_on(client.read_to_end(&mut response)).unwrap();
+        assert!(response == [7; 10_000]);
+
+        assert_eq!(server.join().unwrap(), b"ping");
+        assert_eq!(proxy.join().unwrap(), (4, 10_000));
+    }
+
+    /// Reads from one `Trickle` and writes to another.
+    struct Side {
+        input: Trickle,
+        output: Trickle,
+    }
+
+    impl AsyncRead for Side {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            ctx: &mut Context<'_>,
+            buf: &mut [u8],
+        ) -> Poll<io::Result<usize>> {
+            Pin::new(&mut self.input).poll_read(ctx, buf)
+        }
+    }
+
+    impl AsyncWrite for Side {
+        fn poll_write(
+            mut self: Pin<&mut Self>,
+            ctx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<io::Result<usize>> {
+            Pin::new(&mut self.output).poll_write(ctx, buf)
+        }
+
+        fn poll_flush(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Pin::new(&mut self.output).poll_flush(ctx)
+        }
+
+        fn poll_close(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Pin::new(&mut self.output).poll_close(ctx)
+        }
+    }
+
+    #[test]
+    fn resumes_after_being_dropped() {
+        let request: Vec<u8> = (0..3000).map(|i| i as u8).collect();
+        let response: Vec<u8> = (0..5000).map(|i| (i * 7) as u8).collect();
+        let mut a = Side {
+            input: trickle(&request, 700),
+            output: trickle(b"", 100),
+        };
+        let mut b = Side {
+            input: trickle(&response, 600),
+            output: trickle(b"", 150),
+        };
+        let mut a_to_b = CopyBuffer::with_capacity(512);
+        let mut b_to_a = CopyBuffer::with_capacity(512);
+
+        // Each attempt is dropped after a few polls, some of them with bytes
+        // read but not written yet.
+        for _ in 0..4 {
+            let mut fut = CopyBuffer::copy_bidirectional(&mut a, &mut b, &mut a_to_b, &mut b_to_a);
+            for _ in 0..5 {
+                let _ = (&mut fut).now_or_never();
+            }
+        }
+        let copied = (a_to_b.amount(), b_to_a.amount());
+        assert!(copied.0 < 3000 && copied.1 < 5000);
+        let fut = CopyBuffer::copy_bidirectional(&mut a, &mut b, &mut a_to_b, &mut b_to_a);
+        let rest = wait(fut).unwrap();
+        assert_eq!((copied.0 + rest.0, copied.1 + rest.1), (3000, 5000));
+        assert_eq!((a_to_b.amount(), b_to_a.amount()), (3000, 5000));
+        assert!(b.output.data == request);
+        assert!(a.output.data == response);
+    }
+}
@@ -0,0 +1,116 @@
+use core::pin::Pin;
+use futures_core::future::Future;
+use futures_core::ready;
+use futures_core::task::{Context, Poll};
+use futures_io::{AsyncBufRead, AsyncWrite};
+use pin_project_lite::pin_project;
+use std::io;
+
+/// Copies everything from `reader` to `writer` straight out of the reader's
+/// buffer, then closes `writer`.
+///
+/// Resolves to the number of bytes the returned future copied. Unlike
+/// [`copy`](super::copy), this doesn't need a buffer of its own: it writes
+/// what [`poll_fill_buf`](AsyncBufRead::poll_fill_buf) returns, and
+/// [`consume`](AsyncBufRead::consume)s what was written.
+///
+/// # Cancel safety
+///
+/// Bytes are only consumed from `reader` after they were written, so when
+/// `reader` is borrowed, dropping the future loses no data: copying again
+/// picks up where it left off, and resolves to the number of bytes copied
+/// from there.
+pub fn copy_buf<R, W>(reader: R, writer: &mut W) -> CopyBuf<'_, R, W>
+where
+    R: AsyncBufRead,
+    W: AsyncWrite + Unpin + ?Sized,
+{
+    CopyBuf {
+        reader,
+        writer,
+        amt: 0,
+        need_flush: false,
+        read_done: false,
+    }
+}
+
+pin_project! {
+    /// Future for the [`copy_buf`] function.
+    #[derive(Debug)]
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct CopyBuf<'a, R, W: ?Sized> {
+        #[pin]
+        reader: R,
+        writer: &'a mut W,
+        amt: u64,
+        need_flush: bool,
+        read_done: bool,
+    }
+}
+
+impl<R, W> Future for CopyBuf<'_, R, W>
+where
+    R: AsyncBufRead,
+    W: AsyncWrite + Unpin + ?Sized,
+{
+    type Output = io::Result<u64>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+        let mut writer = Pin::new(&mut **this.writer);
+        while !*this.read_done {
+            let buf = match this.reader.as_mut().poll_fill_buf(ctx) {
+                Poll::Ready(buf) => buf?,
+                Poll::Pending => {
+                    if *this.need_flush {
+                        ready!(writer.as_mut().poll_flush(ctx))?;
+                        *this.need_flush = false;
+                    }
+                    return Poll::Pending;
+                }
+            };
+            if buf.is_empty() {
+                *this.read_done = true;
+                break;
+            }
+
+            let n = ready!(writer.as_mut().poll_write(ctx, buf))?;
+            if n == 0 {
+                return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+            }
+            this.reader.as_mut().consume(n);
+            *this.amt += n as u64;
+            *this.need_flush = true;
+        }
+        ready!(writer.poll_close(ctx))?;
+        Poll::Ready(Ok(*this.amt))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::copy_buf;
+    use crate::future::FutureExt;
+    use crate::io::tests::{trickle, wait};
+    use crate::io::BufReader;
+    use std::vec::Vec;
+
+    #[test]
+    fn resumes_after_being_dropped() {
+        let data: Vec<u8> = (0..5000).map(|i| i as u8).collect();
+        let mut reader = BufReader::with_capacity(512, trickle(&data, 700));
+        let mut writer = trickle(b"", 100);
+
+        // Each attempt is dropped after a few polls.
+        for _ in 0..10 {
+            let mut fut = copy_buf(&mut reader, &mut writer);
+            for _ in 0..5 {
+                let _ = (&mut fut).now_or_never();
+            }
+        }
+        let rest = wait(copy_buf(&mut reader, &mut writer)).unwrap();
+        assert!(rest < 5000);
+        assert!(writer.data == data);
+        assert_eq!(writer.step, 0);
+    }
+}
@@ -0,0 +1,175 @@
+use core::pin::Pin;
+use futures_core::task::{Context, Poll};
+use futures_io::{AsyncBufRead, AsyncRead, AsyncSeek, AsyncWrite, IoSlice, IoSliceMut, SeekFrom};
+use std::boxed::Box;
+use std::io;
+use std::vec::Vec;
+
+/// An in-memory buffer with a position, implementing [`AsyncRead`],
+/// [`AsyncBufRead`] and [`AsyncSeek`].
+///
+/// This is the asynchronous version of [`std::io::Cursor`]. Like there,
+/// [`AsyncWrite`] is implemented for `Cursor<Vec<u8>>`, `Cursor<&mut Vec<u8>>`,
+/// `Cursor<&mut [u8]>` and `Cursor<Box<[u8]>>`; writing overwrites the data
+/// at the current position, and the `Vec`s grow as needed.
+///
+/// ```
+/// use futures_util::future::FutureExt;
+/// use futures_util::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt, Cursor, SeekFrom};
+///
+/// let mut cursor = Cursor::new(Vec::new());
+/// cursor.write_all(b"hello").now_or_never().unwrap().unwrap();
+/// cursor.seek(SeekFrom::Start(1)).now_or_never().unwrap().unwrap();
+///
+/// let mut buf = String::new();
+/// cursor.read_to_string(&mut buf).now_or_never().unwrap().unwrap();
+/// assert_eq!(buf, "ello");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Cursor<T> {
+    inner: io::Cursor<T>,
+}
+
+impl<T> Cursor<T> {
+    /// Creates a new cursor at the start of `inner`.
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner: io::Cursor::new(inner),
+        }
+    }
+
+    /// Returns a reference to the buffer.
+    pub fn get_ref(&self) -> &T {
+        self.inner.get_ref()
+    }
+
+    /// Returns a mutable reference to the buffer.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.inner.get_mut()
+    }
+
+    /// Consumes the cursor, returning the buffer.
+    pub fn into_inner(self) -> T {
+        self.inner.into_inner()
+    }
+
+    /// Returns the current position.
+    pub fn position(&self) -> u64 {
+        self.inner.position()
+    }
+
+    /// Sets the current position, which may be past the end of the buffer.
+    pub fn set_position(&mut self, pos: u64) {
+        self.inner.set_position(pos)
+    }
+}
+
+impl<T: AsRef<[u8]> + Unpin> AsyncRead for Cursor<T> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        Poll::Ready(io::Read::read(&mut self.inner, buf))
+    }
+
+    fn poll_read_vectored(
+        mut self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        bufs: &mut [IoSliceMut<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Poll::Ready(io::Read::read_vectored(&mut self.inner, bufs))
+    }
+}
+
+impl<T: AsRef<[u8]> + Unpin> AsyncBufRead for Cursor<T> {
+    fn poll_fill_buf(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        Poll::Ready(io::BufRead::fill_buf(&mut self.get_mut().inner))
+    }
+
+    fn consume(mut self: Pin<&mut Self>, amt: usize) {
+        io::BufRead::consume(&mut self.inner, amt)
+    }
+}
+
+impl<T: AsRef<[u8]> + Unpin> AsyncSeek for Cursor<T> {
+    fn poll_seek(
+        mut self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        pos: SeekFrom,
+    ) -> Poll<io::Result<u64>> {
+        Poll::Ready(io::Seek::seek(&mut self.inner, pos))
+    }
+}
+
+macro_rules! impl_async_write {
+    ($($ty:ty),*) => {$(
+        impl AsyncWrite for Cursor<$ty> {
+            fn poll_write(
+                mut self: Pin<&mut Self>,
+                _: &mut Context<'_>,
+                buf: &[u8],
+            ) -> Poll<io::Result<usize>> {
+                Poll::Ready(io::Write::write(&mut self.inner, buf))
+            }
+
+            fn poll_write_vectored(
+                mut self: Pin<&mut Self>,
+                _: &mut Context<'_>,
+                bufs: &[IoSlice<'_>],
+            ) -> Poll<io::Result<usize>> {
+                Poll::Ready(io::Write::write_vectored(&mut self.inner, bufs))
+            }
+
+            fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+                Poll::Ready(Ok(()))
+            }
+
+            fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+                Poll::Ready(Ok(()))
+            }
+        }
+    )*};
+}
+
+impl_async_write!(Vec<u8>, &mut Vec<u8>, &mut [u8], Box<[u8]>);
+
+#[cfg(test)]
+mod tests {
+    use super::Cursor;
+    use crate::io::tests::wait;
+    use crate::io::{AsyncBufReadExt, AsyncSeekExt, AsyncWriteExt, ErrorKind, SeekFrom};
+    use std::string::String;
+    use std::vec;
+
+    #[test]
+    fn writes_overwrite_and_extend() {
+        let mut cursor = Cursor::new(vec![1, 2, 3]);
+        wait(cursor.write_all(&[9])).unwrap();
+        assert_eq!(cursor.position(), 1);
+        cursor.set_position(5);
+        wait(cursor.write_all(&[7])).unwrap();
+        assert_eq!(cursor.into_inner(), [9, 2, 3, 0, 0, 7]);
+    }
+
+    #[test]
+    fn fixed_buffers_run_out_of_space() {
+        let mut buf = [0; 3];
+        let mut cursor = Cursor::new(&mut buf[..]);
+        let err = wait(cursor.write_all(b"abcd")).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::WriteZero);
+        assert_eq!(&buf, b"abc");
+    }
+
+    #[test]
+    fn reads_lines_after_seeking() {
+        let mut cursor = Cursor::new("one\ntwo\nthree\n");
+        assert_eq!(wait(cursor.seek(SeekFrom::End(-6))).unwrap(), 8);
+        let mut line = String::new();
+        wait(cursor.read_line(&mut line)).unwrap();
+        assert_eq!(line, "three\n");
+
+        let err = wait(cursor.seek(SeekFrom::Current(-20))).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    }
+}
@@ -0,0 +1,252 @@
+use core::pin::Pin;
+use futures_core::task::{Context, Poll, Waker};
+use futures_io::{AsyncRead, AsyncWrite, IoSlice};
+use std::collections::VecDeque;
+use std::io::{self, Read};
+use std::sync::{Arc, Mutex};
+
+/// Creates a pair of connected in-memory streams, like a socket pair.
+///
+/// Whatever is written to one end can be read from the other. Each direction
+/// buffers up to `capacity` bytes; writes wait until the other end reads
+/// when that is full. Closing an end, or dropping it, makes the other end
+/// read end-of-file once it drained the buffer, and writing to a dropped end
+/// fails with [`ErrorKind::BrokenPipe`](io::ErrorKind::BrokenPipe).
+///
+/// # Panics
+///
+/// Panics if `capacity` is zero.
+///
+/// ```
+/// use futures_util::future::FutureExt;
+/// use futures_util::io::{duplex, AsyncReadExt, AsyncWriteExt};
+///
+/// let (mut client, mut server) = duplex(64);
+/// client.write_all(b"ping").now_or_never().unwrap().unwrap();
+///
+/// let mut buf = [0; 4];
+/// server.read_exact(&mut buf).now_or_never().unwrap().unwrap();
+/// assert_eq!(&buf, b"ping");
+/// ```
+pub fn duplex(capacity: usize) -> (DuplexStream, DuplexStream) {
+    assert!(capacity > 0, "`capacity` must be non-zero");
+    let one = Arc::new(Mutex::new(Pipe::new(capacity)));
+    let two = Arc::new(Mutex::new(Pipe::new(capacity)));
+    (
+        DuplexStream {
+            read: one.clone(),
+            write: two.clone(),
+        },
+        DuplexStream {
+            read: two,
+            write: one,
+        },
+    )
+}
+
+/// One end of an in-memory stream created by [`duplex`].
+#[derive(Debug)]
+pub struct DuplexStream {
+    read: Arc<Mutex<Pipe>>,
+    write: Arc<Mutex<Pipe>>,
+}
+
+/// The bytes in flight in one direction.
+#[derive(Debug)]
+struct Pipe {
+    buf: VecDeque<u8>,
+    capacity: usize,
+    closed: bool,
+    read_waker: Option<Waker>,
+    write_waker: Option<Waker>,
+}
+
+impl Pipe {
+    fn new(capacity: usize) -> Self {
+        Self {
+            buf: VecDeque::new(),
+            capacity,
+            closed: false,
+            read_waker: None,
+            write_waker: None,
+        }
+    }
+
+    fn close(&mut self) {
+        self.closed = true;
+        // Both sides must learn about it: readers see end-of-file, writers
+        // an error.
+        if let Some(waker) = self.read_waker.take() {
+            waker.wake();
+        }
+        if let Some(waker) = self.write_waker.take() {
+            waker.wake();
+        }
+    }
+
+    fn poll_read(&mut self, ctx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        if self.buf.is_empty() && !self.closed && !buf.is_empty() {
+            self.read_waker = Some(ctx.waker().clone());
+            return Poll::Pending;
+        }
+        let n = self.buf.read(buf)?;
+        if n > 0 {
+            if let Some(waker) = self.write_waker.take() {
+                waker.wake();
+            }
+        }
+        Poll::Ready(Ok(n))
+    }
+
+    fn poll_write(
+        &mut self,
+        ctx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        if self.closed {
+            return Poll::Ready(Err(io::ErrorKind::BrokenPipe.into()));
+        }
+        if bufs.iter().all(|buf| buf.is_empty()) {
+            return Poll::Ready(Ok(0));
+        }
+        let available = self.capacity - self.buf.len();
+        if available == 0 {
+            self.write_waker = Some(ctx.waker().clone());
+            return Poll::Pending;
+        }
+        let mut left = available;
+        for buf in bufs {
+            let n = buf.len().min(left);
+            self.buf.extend(&buf[..n]);
+            left -= n;
+        }
+        if let Some(waker) = self.read_waker.take() {
+            waker.wake();
+        }
+        Poll::Ready(Ok(available - left))
+    }
+}
+
+impl AsyncRead for DuplexStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        ctx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        self.read.lock().unwrap().poll_read(ctx, buf)
+    }
+}
+
+impl AsyncWrite for DuplexStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        ctx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.write
+            .lock()
+            .unwrap()
+            .poll_write(ctx, &[IoSlice::new(buf)])
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        ctx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        self.write.lock().unwrap().poll_write(ctx, bufs)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.write.lock().unwrap().close();
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl Drop for DuplexStream {
+    fn drop(&mut self) {
+        // Don't panic in drop if the other end panicked while holding a lock.
+        if let Ok(mut pipe) = self.write.lock() {
+            pipe.close();
+        }
+        if let Ok(mut pipe) = self.read.lock() {
+            pipe.close();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::duplex;
+    use crate::future::FutureExt;
+    use crate::io::tests::wait;
+    use crate::io::{AsyncReadExt, AsyncWriteExt, ErrorKind};
+    use core::task::{Context, Waker};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::task::Wake;
+    use std::thread;
+    use std::vec::Vec;
+
+    struct CountWakes(AtomicUsize);
+
+    impl Wake for CountWakes {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn full_buffer_applies_backpressure() {
+        let (mut a, mut b) = duplex(4);
+        assert_eq!(wait(a.write(b"abcdef")).unwrap(), 4);
+
+        let wakes = Arc::new(CountWakes(AtomicUsize::new(0)));
+        let waker = Waker::from(wakes.clone());
+        let mut ctx = Context::from_waker(&waker);
+        let mut write = a.write(b"ef");
+        assert!(write.poll_unpin(&mut ctx).is_pending());
+
+        let mut buf = [0; 3];
+        assert_eq!(wait(b.read(&mut buf)).unwrap(), 3);
+        assert_eq!(wakes.0.load(Ordering::SeqCst), 1);
+        assert_eq!(wait(write).unwrap(), 2);
+    }
+
+    #[test]
+    fn close_and_drop() {
+        let (mut a, mut b) = duplex(16);
+        wait(a.write_all(b"bye")).unwrap();
+        wait(a.close()).unwrap();
+        let err = wait(a.write(b"x")).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::BrokenPipe);
+
+        let mut buf = Vec::new();
+        wait(b.read_to_end(&mut buf)).unwrap();
+        assert_eq!(buf, b"bye");
+
+        // The other direction is still open until `a` goes away.
+        wait(b.write_all(b"late")).unwrap();
+        drop(a);
+        let err = wait(b.write(b"x")).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::BrokenPipe);
+    }
+
+    #[test]
+    fn across_threads() {
+        let (mut a, mut b) = duplex(7);
+        let data: Vec<u8> = (0..=255).cycle().take(10_000).collect();
+        let expected = data.clone();
+        let writer = thread::spawn(move || {
+            futures_executor::block_on(a.write_all(&data)).unwrap();
+        });
+        let mut buf = Vec::new();
+        futures_executor::block_on(b.read_to_end(&mut buf)).unwrap();
+        writer.join().unwrap();
+        assert!(buf == expected);
+    }
+}
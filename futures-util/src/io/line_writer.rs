@@ -0,0 +1,211 @@
+use super::buf_writer::BufWriter;
+use core::pin::Pin;
+use futures_core::ready;
+use futures_core::task::{Context, Poll};
+use futures_io::{AsyncWrite, IoSlice};
+use pin_project_lite::pin_project;
+use std::io;
+
+pin_project! {
+    /// Like [`BufWriter`], but writes out the buffer at the end of every
+    /// line.
+    ///
+    /// Complete lines are written straight to the underlying writer; only a
+    /// trailing partial line stays in the buffer.
+    #[derive(Debug)]
+    pub struct LineWriter<W> {
+        #[pin]
+        buf_writer: BufWriter<W>,
+    }
+}
+
+impl<W: AsyncWrite> LineWriter<W> {
+    /// Creates a new `LineWriter` with a buffer of 1 KiB.
+    pub fn new(inner: W) -> Self {
+        Self::with_capacity(1024, inner)
+    }
+
+    /// Creates a new `LineWriter` with a buffer of `capacity` bytes.
+    pub fn with_capacity(capacity: usize, inner: W) -> Self {
+        Self {
+            buf_writer: BufWriter::with_capacity(capacity, inner),
+        }
+    }
+
+    /// Writes out the buffer if it holds a complete line.
+    fn flush_if_completed_line(
+        self: Pin<&mut Self>,
+        ctx: &mut Context<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.project();
+        match this.buf_writer.buffer().last() {
+            Some(b'\n') => this.buf_writer.flush_buf(ctx),
+            _ => Poll::Ready(Ok(())),
+        }
+    }
+}
+
+impl<W> LineWriter<W> {
+    /// Returns a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        self.buf_writer.get_ref()
+    }
+
+    /// Returns a mutable reference to the underlying writer.
+    ///
+    /// Writing to it directly skips ahead of the buffered data.
+    pub fn get_mut(&mut self) -> &mut W {
+        self.buf_writer.get_mut()
+    }
+
+    /// Returns a pinned mutable reference to the underlying writer.
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut W> {
+        self.project().buf_writer.get_pin_mut()
+    }
+
+    /// Consumes the `LineWriter`, returning the underlying writer.
+    ///
+    /// Any buffered data is lost.
+    pub fn into_inner(self) -> W {
+        self.buf_writer.into_inner()
+    }
+
+    /// Returns the buffered data.
+    pub fn buffer(&self) -> &[u8] {
+        self.buf_writer.buffer()
+    }
+}
+
+impl<W: AsyncWrite> AsyncWrite for LineWriter<W> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        ctx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let lines_len = match buf.iter().rposition(|&b| b == b'\n') {
+            Some(i) => i + 1,
+            None => {
+                ready!(self.as_mut().flush_if_completed_line(ctx))?;
+                return self.project().buf_writer.poll_write(ctx, buf);
+            }
+        };
+
+        // Everything up to the last newline goes out directly, after what
+        // was buffered before.
+        let mut buf_writer = self.project().buf_writer;
+        ready!(buf_writer.as_mut().poll_flush(ctx))?;
+        let flushed = ready!(buf_writer
+            .as_mut()
+            .get_pin_mut()
+            .poll_write(ctx, &buf[..lines_len]))?;
+        if flushed == 0 {
+            return Poll::Ready(Ok(0));
+        }
+
+        // Buffer as much of the rest as makes sense: the partial last line,
+        // or the part of the lines that wasn't written, up to a line end if
+        // it doesn't fit.
+        let tail = if flushed >= lines_len {
+            &buf[flushed..]
+        } else if lines_len - flushed <= buf_writer.capacity() {
+            &buf[flushed..lines_len]
+        } else {
+            let scan = &buf[flushed..flushed + buf_writer.capacity()];
+            match scan.iter().rposition(|&b| b == b'\n') {
+                Some(i) => &scan[..i + 1],
+                None => scan,
+            }
+        };
+        let buffered = buf_writer.write_to_buf(tail);
+        Poll::Ready(Ok(flushed + buffered))
+    }
+
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        ctx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        let last_line = bufs.iter().rposition(|buf| buf.contains(&b'\n'));
+        let Some(last_line) = last_line else {
+            ready!(self.as_mut().flush_if_completed_line(ctx))?;
+            return self.project().buf_writer.poll_write_vectored(ctx, bufs);
+        };
+
+        let mut buf_writer = self.project().buf_writer;
+        ready!(buf_writer.as_mut().poll_flush(ctx))?;
+        let (lines, tail) = bufs.split_at(last_line + 1);
+        let flushed = ready!(buf_writer
+            .as_mut()
+            .get_pin_mut()
+            .poll_write_vectored(ctx, lines))?;
+        if flushed == 0 {
+            return Poll::Ready(Ok(0));
+        }
+        let lines_len = lines.iter().map(|buf| buf.len()).sum::<usize>();
+        if flushed < lines_len {
+            return Poll::Ready(Ok(flushed));
+        }
+
+        let mut buffered = 0;
+        for buf in tail {
+            let n = buf_writer.as_mut().write_to_buf(buf);
+            buffered += n;
+            if n < buf.len() {
+                break;
+            }
+        }
+        Poll::Ready(Ok(flushed + buffered))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.project().buf_writer.poll_flush(ctx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.project().buf_writer.poll_close(ctx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LineWriter;
+    use crate::io::tests::{trickle, wait};
+    use crate::io::{AsyncWriteExt, IoSlice};
+
+    #[test]
+    fn writes_out_complete_lines() {
+        let mut writer = LineWriter::with_capacity(16, trickle(b"", 64));
+        wait(writer.write_all(b"partial")).unwrap();
+        assert_eq!(writer.get_ref().data, b"");
+
+        wait(writer.write_all(b" line\nnext")).unwrap();
+        assert_eq!(writer.get_ref().data, b"partial line\n");
+        assert_eq!(writer.buffer(), b"next");
+
+        wait(writer.write_all(b"\nx")).unwrap();
+        assert_eq!(writer.get_ref().data, b"partial line\nnext\n");
+        assert_eq!(writer.buffer(), b"x");
+
+        wait(writer.close()).unwrap();
+        assert_eq!(writer.into_inner().data, b"partial line\nnext\nx");
+    }
+
+    #[test]
+    fn short_writes_buffer_the_rest() {
+        let mut writer = LineWriter::with_capacity(16, trickle(b"", 3));
+        assert_eq!(wait(writer.write(b"ab\ncd\nef")).unwrap(), 6);
+        assert_eq!(writer.get_ref().data, b"ab\n");
+        assert_eq!(writer.buffer(), b"cd\n");
+        wait(writer.flush()).unwrap();
+        assert_eq!(writer.get_ref().data, b"ab\ncd\n");
+    }
+
+    #[test]
+    fn vectored_writes() {
+        let mut writer = LineWriter::with_capacity(16, trickle(b"", 64));
+        let mut bufs = [IoSlice::new(b"a\n"), IoSlice::new(b"b"), IoSlice::new(b"c")];
+        wait(writer.write_all_vectored(&mut bufs)).unwrap();
+        assert_eq!(writer.get_ref().data, b"a\n");
+        assert_eq!(writer.buffer(), b"bc");
+    }
+}
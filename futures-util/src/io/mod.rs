@@ -0,0 +1,499 @@
+//! Asynchronous I/O.
+//!
+//! This module re-exports the [`AsyncRead`], [`AsyncWrite`], [`AsyncSeek`]
+//! and [`AsyncBufRead`] traits of `futures_io`, and adds the
+//! [`AsyncReadExt`], [`AsyncWriteExt`], [`AsyncSeekExt`] and
+//! [`AsyncBufReadExt`] extension traits, which turn their poll methods into
+//! named futures and streams.
+//!
+//! [`BufReader`], [`BufWriter`] and [`LineWriter`] add buffering to readers
+//! and writers. [`Cursor`] and [`duplex`] provide in-memory I/O, which is
+//! handy for testing. [`copy`], [`copy_buf`] and [`copy_bidirectional`] move
+//! data between readers and writers, each resolving to the number of bytes
+//! its future copied; [`CopyBuffer`] makes a copy resumable after its future
+//! was dropped.
+
+use std::string::String;
+use std::vec::Vec;
+
+// Re-exported so that users don't have to mix `std::io` and this module.
+#[doc(no_inline)]
+pub use std::io::{Error, ErrorKind, IoSlice, IoSliceMut, Result, SeekFrom};
+
+#[doc(no_inline)]
+pub use futures_io::{AllowStdIo, AsyncBufRead, AsyncRead, AsyncSeek, AsyncWrite, BlockOn};
+
+const DEFAULT_BUF_SIZE: usize = 8 * 1024;
+
+mod buf_reader;
+pub use self::buf_reader::BufReader;
+
+mod buf_writer;
+pub use self::buf_writer::BufWriter;
+
+mod close;
+pub use self::close::Close;
+
+mod copy;
+pub use self::copy::{copy, Copy, CopyBuffer};
+
+mod copy_bidirectional;
+pub use self::copy_bidirectional::{copy_bidirectional, CopyBidirectional};
+
+mod copy_buf;
+pub use self::copy_buf::{copy_buf, CopyBuf};
+
+mod cursor;
+pub use self::cursor::Cursor;
+
+mod duplex;
+pub use self::duplex::{duplex, DuplexStream};
+
+mod flush;
+pub use self::flush::Flush;
+
+mod line_writer;
+pub use self::line_writer::LineWriter;
+
+mod lines;
+pub use self::lines::Lines;
+
+mod read;
+pub use self::read::Read;
+
+mod read_exact;
+pub use self::read_exact::ReadExact;
+
+mod read_line;
+pub use self::read_line::ReadLine;
+
+mod read_to_end;
+pub use self::read_to_end::ReadToEnd;
+
+mod read_to_string;
+pub use self::read_to_string::ReadToString;
+
+mod read_until;
+pub use self::read_until::ReadUntil;
+
+mod read_vectored;
+pub use self::read_vectored::ReadVectored;
+
+mod seek;
+pub use self::seek::Seek;
+
+mod write;
+pub use self::write::Write;
+
+mod write_all;
+pub use self::write_all::WriteAll;
+
+mod write_all_vectored;
+pub use self::write_all_vectored::WriteAllVectored;
+
+impl<R: AsyncRead + ?Sized> AsyncReadExt for R {}
+
+/// An extension trait which adds utility methods to [`AsyncRead`] types.
+pub trait AsyncReadExt: AsyncRead {
+    /// Tries to read some bytes into `buf`, resolving to the number of bytes
+    /// read. Zero means end-of-file, unless `buf` is empty.
+    fn read<'a>(&'a mut self, buf: &'a mut [u8]) -> Read<'a, Self>
+    where
+        Self: Unpin,
+    {
+        Read::new(self, buf)
+    }
+
+    /// Like [`read`](AsyncReadExt::read), except that it reads into a slice
+    /// of buffers.
+    fn read_vectored<'a>(&'a mut self, bufs: &'a mut [IoSliceMut<'a>]) -> ReadVectored<'a, Self>
+    where
+        Self: Unpin,
+    {
+        ReadVectored::new(self, bufs)
+    }
+
+    /// Reads exactly enough bytes to fill `buf`.
+    ///
+    /// Fails with [`ErrorKind::UnexpectedEof`] if the reader ends first, in
+    /// which case the contents of `buf` are unspecified.
+    fn read_exact<'a>(&'a mut self, buf: &'a mut [u8]) -> ReadExact<'a, Self>
+    where
+        Self: Unpin,
+    {
+        ReadExact::new(self, buf)
+    }
+
+    /// Reads all bytes until end-of-file, appending them to `buf` and
+    /// resolving to the number of bytes read.
+    fn read_to_end<'a>(&'a mut self, buf: &'a mut Vec<u8>) -> ReadToEnd<'a, Self>
+    where
+        Self: Unpin,
+    {
+        ReadToEnd::new(self, buf)
+    }
+
+    /// Reads all bytes until end-of-file, appending them to `buf` and
+    /// resolving to the number of bytes read.
+    ///
+    /// If the data is not valid UTF-8, this fails with
+    /// [`ErrorKind::InvalidData`] and leaves `buf` unchanged.
+    ///
+    /// ```
+    /// use futures_util::future::FutureExt;
+    /// use futures_util::io::{AsyncReadExt, ErrorKind};
+    ///
+    /// let mut buf = String::from("kept");
+    /// let res = (&b"\xff"[..]).read_to_string(&mut buf).now_or_never().unwrap();
+    /// assert_eq!(res.unwrap_err().kind(), ErrorKind::InvalidData);
+    /// assert_eq!(buf, "kept");
+    /// ```
+    fn read_to_string<'a>(&'a mut self, buf: &'a mut String) -> ReadToString<'a, Self>
+    where
+        Self: Unpin,
+    {
+        ReadToString::new(self, buf)
+    }
+}
+
+impl<W: AsyncWrite + ?Sized> AsyncWriteExt for W {}
+
+/// An extension trait which adds utility methods to [`AsyncWrite`] types.
+pub trait AsyncWriteExt: AsyncWrite {
+    /// Flushes the writer, making sure all buffered data reaches its
+    /// destination.
+    fn flush(&mut self) -> Flush<'_, Self>
+    where
+        Self: Unpin,
+    {
+        Flush::new(self)
+    }
+
+    /// Closes the writer, flushing it first.
+    fn close(&mut self) -> Close<'_, Self>
+    where
+        Self: Unpin,
+    {
+        Close::new(self)
+    }
+
+    /// Tries to write some bytes from `buf`, resolving to the number of
+    /// bytes written.
+    fn write<'a>(&'a mut self, buf: &'a [u8]) -> Write<'a, Self>
+    where
+        Self: Unpin,
+    {
+        Write::new(self, buf)
+    }
+
+    /// Writes all of `buf`.
+    ///
+    /// Fails with [`ErrorKind::WriteZero`] if the writer stops accepting
+    /// bytes.
+    fn write_all<'a>(&'a mut self, buf: &'a [u8]) -> WriteAll<'a, Self>
+    where
+        Self: Unpin,
+    {
+        WriteAll::new(self, buf)
+    }
+
+    /// Writes all of the buffers in `bufs`, using vectored writes.
+    ///
+    /// The slices in `bufs` are advanced past the written data, so their
+    /// contents are unspecified afterwards.
+    fn write_all_vectored<'a>(
+        &'a mut self,
+        bufs: &'a mut [IoSlice<'a>],
+    ) -> WriteAllVectored<'a, Self>
+    where
+        Self: Unpin,
+    {
+        WriteAllVectored::new(self, bufs)
+    }
+}
+
+impl<S: AsyncSeek + ?Sized> AsyncSeekExt for S {}
+
+/// An extension trait which adds utility methods to [`AsyncSeek`] types.
+pub trait AsyncSeekExt: AsyncSeek {
+    /// Seeks to `pos`, resolving to the new position from the start.
+    fn seek(&mut self, pos: SeekFrom) -> Seek<'_, Self>
+    where
+        Self: Unpin,
+    {
+        Seek::new(self, pos)
+    }
+
+    /// Resolves to the current position from the start.
+    fn stream_position(&mut self) -> Seek<'_, Self>
+    where
+        Self: Unpin,
+    {
+        self.seek(SeekFrom::Current(0))
+    }
+}
+
+impl<R: AsyncBufRead + ?Sized> AsyncBufReadExt for R {}
+
+/// An extension trait which adds utility methods to [`AsyncBufRead`] types.
+pub trait AsyncBufReadExt: AsyncBufRead {
+    /// Reads bytes into `buf` until the delimiter `byte` or end-of-file,
+    /// resolving to the number of bytes read.
+    ///
+    /// The delimiter is included in `buf` if it was found.
+    fn read_until<'a>(&'a mut self, byte: u8, buf: &'a mut Vec<u8>) -> ReadUntil<'a, Self>
+    where
+        Self: Unpin,
+    {
+        ReadUntil::new(self, byte, buf)
+    }
+
+    /// Reads a line, including its `\n` if any, and appends it to `buf`,
+    /// resolving to the number of bytes read.
+    ///
+    /// If the line is not valid UTF-8, this fails with
+    /// [`ErrorKind::InvalidData`] and leaves `buf` unchanged.
+    fn read_line<'a>(&'a mut self, buf: &'a mut String) -> ReadLine<'a, Self>
+    where
+        Self: Unpin,
+    {
+        ReadLine::new(self, buf)
+    }
+
+    /// Turns this reader into a stream of its lines, without their `\n` or
+    /// `\r\n` endings.
+    fn lines(self) -> Lines<Self>
+    where
+        Self: Sized,
+    {
+        Lines::new(self)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::future::FutureExt;
+    use crate::stream::StreamExt;
+    use core::pin::Pin;
+    use core::task::{Context, Poll};
+    use std::string::ToString;
+    use std::vec;
+
+    /// Reads and writes at most `step` bytes per call, returning `Pending`
+    /// before each call that makes progress.
+    pub(crate) struct Trickle {
+        pub(crate) data: Vec<u8>,
+        pub(crate) pos: usize,
+        pub(crate) step: usize,
+        ready: bool,
+    }
+
+    pub(crate) fn trickle(data: &[u8], step: usize) -> Trickle {
+        Trickle {
+            data: data.to_vec(),
+            pos: 0,
+            step,
+            ready: false,
+        }
+    }
+
+    impl Trickle {
+        fn poll_ready(&mut self, ctx: &mut Context<'_>) -> Poll<()> {
+            self.ready = !self.ready;
+            if self.ready {
+                ctx.waker().wake_by_ref();
+                Poll::Pending
+            } else {
+                Poll::Ready(())
+            }
+        }
+    }
+
+    impl AsyncRead for Trickle {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            ctx: &mut Context<'_>,
+            buf: &mut [u8],
+        ) -> Poll<Result<usize>> {
+            futures_core::ready!(self.poll_ready(ctx));
+            let n = buf.len().min(self.step).min(self.data.len() - self.pos);
+            buf[..n].copy_from_slice(&self.data[self.pos..self.pos + n]);
+            self.pos += n;
+            Poll::Ready(Ok(n))
+        }
+    }
+
+    impl AsyncWrite for Trickle {
+        fn poll_write(
+            mut self: Pin<&mut Self>,
+            ctx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<Result<usize>> {
+            futures_core::ready!(self.poll_ready(ctx));
+            let n = buf.len().min(self.step);
+            self.data.extend_from_slice(&buf[..n]);
+            Poll::Ready(Ok(n))
+        }
+
+        fn poll_write_vectored(
+            mut self: Pin<&mut Self>,
+            ctx: &mut Context<'_>,
+            bufs: &[IoSlice<'_>],
+        ) -> Poll<Result<usize>> {
+            futures_core::ready!(self.poll_ready(ctx));
+            let mut left = self.step;
+            for buf in bufs {
+                let n = buf.len().min(left);
+                self.data.extend_from_slice(&buf[..n]);
+                left -= n;
+            }
+            Poll::Ready(Ok(self.step - left))
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<()>> {
+            self.step = 0;
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    impl AsyncSeek for Trickle {
+        fn poll_seek(
+            mut self: Pin<&mut Self>,
+            _: &mut Context<'_>,
+            pos: SeekFrom,
+        ) -> Poll<Result<u64>> {
+            self.pos = match pos {
+                SeekFrom::Start(n) => n as usize,
+                SeekFrom::End(n) => (self.data.len() as i64 + n) as usize,
+                SeekFrom::Current(n) => (self.pos as i64 + n) as usize,
+            };
+            Poll::Ready(Ok(self.pos as u64))
+        }
+    }
+
+    /// Polls `fut` to completion with a no-op waker.
+    pub(crate) fn wait<F: core::future::Future + Unpin>(mut fut: F) -> F::Output {
+        loop {
+            if let Some(out) = (&mut fut).now_or_never() {
+                return out;
+            }
+        }
+    }
+
+    #[test]
+    fn read_and_read_exact() {
+        let mut reader = trickle(b"hello world", 4);
+        let mut buf = [0; 8];
+        assert_eq!(wait(reader.read(&mut buf)).unwrap(), 4);
+        assert_eq!(&buf[..4], b"hell");
+
+        let mut buf = [0; 5];
+        wait(reader.read_exact(&mut buf)).unwrap();
+        assert_eq!(&buf, b"o wor");
+
+        let err = wait(reader.read_exact(&mut buf)).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn read_vectored_spans_buffers() {
+        let mut reader = &b"abcdef"[..];
+        let (mut a, mut b) = ([0; 2], [0; 8]);
+        let mut bufs = [IoSliceMut::new(&mut a), IoSliceMut::new(&mut b)];
+        assert_eq!(wait(reader.read_vectored(&mut bufs)).unwrap(), 6);
+        assert_eq!(&a, b"ab");
+        assert_eq!(&b[..4], b"cdef");
+    }
+
+    #[test]
+    fn read_to_end_appends() {
+        let data: Vec<u8> = (0..1000).map(|i| i as u8).collect();
+        let mut reader = trickle(&data, 77);
+        let mut buf = vec![9];
+        assert_eq!(wait(reader.read_to_end(&mut buf)).unwrap(), 1000);
+        assert_eq!(buf[0], 9);
+        assert!(buf[1..] == data[..]);
+    }
+
+    #[test]
+    fn read_to_string_keeps_buffer_on_invalid_utf8() {
+        let mut buf = "é".to_string();
+        let mut reader = trickle("über".as_bytes(), 1);
+        assert_eq!(wait(reader.read_to_string(&mut buf)).unwrap(), 5);
+        assert_eq!(buf, "éüber");
+
+        let mut reader = trickle(b"ok \xc3\x28", 2);
+        let err = wait(reader.read_to_string(&mut buf)).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(buf, "éüber");
+
+        // Dropped halfway through a character: the complete part is kept.
+        let mut reader = trickle("aü".as_bytes(), 2);
+        let mut fut = reader.read_to_string(&mut buf);
+        assert!((&mut fut).now_or_never().is_none());
+        assert!((&mut fut).now_or_never().is_none());
+        drop(fut);
+        assert_eq!(buf, "éübera");
+    }
+
+    #[test]
+    fn writes() {
+        let mut writer = trickle(b"", 3);
+        assert_eq!(wait(writer.write(b"abcdef")).unwrap(), 3);
+        wait(writer.write_all(b"ghijklm")).unwrap();
+        assert_eq!(writer.data, b"abcghijklm");
+
+        let mut bufs = [
+            IoSlice::new(b"no"),
+            IoSlice::new(b""),
+            IoSlice::new(b"pqrs"),
+        ];
+        wait(writer.write_all_vectored(&mut bufs)).unwrap();
+        assert_eq!(writer.data, b"abcghijklmnopqrs");
+
+        wait(writer.flush()).unwrap();
+        wait(writer.close()).unwrap();
+        let err = wait(writer.write_all(b"x")).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::WriteZero);
+    }
+
+    #[test]
+    fn seek_and_stream_position() {
+        let mut reader = trickle(b"0123456789", 10);
+        assert_eq!(wait(reader.seek(SeekFrom::End(-3))).unwrap(), 7);
+        assert_eq!(wait(reader.stream_position()).unwrap(), 7);
+        let mut buf = Vec::new();
+        wait(reader.read_to_end(&mut buf)).unwrap();
+        assert_eq!(buf, b"789");
+    }
+
+    #[test]
+    fn buffered_reads() {
+        let mut reader = &b"one\ntwo\r\nthree"[..];
+        let mut buf = Vec::new();
+        assert_eq!(wait(reader.read_until(b'\n', &mut buf)).unwrap(), 4);
+        assert_eq!(buf, b"one\n");
+
+        let mut line = String::new();
+        assert_eq!(wait(reader.read_line(&mut line)).unwrap(), 5);
+        assert_eq!(line, "two\r\n");
+
+        let mut reader = &b"bad \xff line\n"[..];
+        let err = wait(reader.read_line(&mut line)).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(line, "two\r\n");
+
+        let lines: Vec<_> = wait(
+            (&b"a\nb\r\n\nc"[..])
+                .lines()
+                .map(|line| line.unwrap())
+                .collect::<Vec<_>>(),
+        );
+        assert_eq!(lines, ["a", "b", "", "c"]);
+    }
+}
@@ -0,0 +1,85 @@
+use futures_core::future::Future;
+use futures_core::ready;
+use futures_core::task::{Context, Poll};
+use futures_io::AsyncRead;
+use std::io;
+use std::pin::Pin;
+use std::vec::Vec;
+
+/// Future for the [`read_to_end`](super::AsyncReadExt::read_to_end) method.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct ReadToEnd<'a, R: ?Sized> {
+    reader: &'a mut R,
+    buf: &'a mut Vec<u8>,
+    start_len: usize,
+}
+
+impl<R: ?Sized + Unpin> Unpin for ReadToEnd<'_, R> {}
+
+impl<'a, R: AsyncRead + ?Sized + Unpin> ReadToEnd<'a, R> {
+    pub(super) fn new(reader: &'a mut R, buf: &'a mut Vec<u8>) -> Self {
+        let start_len = buf.len();
+        Self {
+            reader,
+            buf,
+            start_len,
+        }
+    }
+}
+
+/// Truncates the buffer to the bytes actually read when dropped, including
+/// on early returns and panics.
+struct Guard<'a> {
+    buf: &'a mut Vec<u8>,
+    len: usize,
+}
+
+impl Drop for Guard<'_> {
+    fn drop(&mut self) {
+        self.buf.truncate(self.len);
+    }
+}
+
+/// Reads into `buf` until end-of-file, returning the number of bytes
+/// appended since its length was `start_len`.
+///
+/// The buffer grows with its capacity, so that small readers don't pay for
+/// zeroing large chunks while large reads still get large buffers.
+pub(super) fn read_to_end_internal<R: AsyncRead + ?Sized>(
+    mut reader: Pin<&mut R>,
+    ctx: &mut Context<'_>,
+    buf: &mut Vec<u8>,
+    start_len: usize,
+) -> Poll<io::Result<usize>> {
+    let mut g = Guard {
+        len: buf.len(),
+        buf,
+    };
+    loop {
+        if g.len == g.buf.len() {
+            g.buf.reserve(32);
+            let capacity = g.buf.capacity();
+            g.buf.resize(capacity, 0);
+        }
+
+        let buf = &mut g.buf[g.len..];
+        match ready!(reader.as_mut().poll_read(ctx, buf)) {
+            Ok(0) => return Poll::Ready(Ok(g.len - start_len)),
+            Ok(n) => {
+                assert!(n <= buf.len(), "reader returned more bytes than requested");
+                g.len += n;
+            }
+            Err(e) => return Poll::Ready(Err(e)),
+        }
+    }
+}
+
+impl<R: AsyncRead + ?Sized + Unpin> Future for ReadToEnd<'_, R> {
+    type Output = io::Result<usize>;
+
+    fn poll(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        read_to_end_internal(Pin::new(&mut this.reader), ctx, this.buf, this.start_len)
+    }
+}
@@ -0,0 +1,90 @@
+use super::read_to_end::read_to_end_internal;
+use futures_core::future::Future;
+use futures_core::ready;
+use futures_core::task::{Context, Poll};
+use futures_io::AsyncRead;
+use std::pin::Pin;
+use std::string::String;
+use std::vec::Vec;
+use std::{io, mem, str};
+
+/// Future for the [`read_to_string`](super::AsyncReadExt::read_to_string)
+/// method.
+///
+/// Like `std::io::Read::read_to_string`, the string is left as it was if
+/// the data read is not valid UTF-8. If the future is dropped before
+/// completing, the string keeps the valid UTF-8 read so far.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct ReadToString<'a, R: ?Sized> {
+    reader: &'a mut R,
+    buf: &'a mut String,
+    /// The contents of `buf` while reading, which may end in an incomplete
+    /// character.
+    bytes: Vec<u8>,
+    start_len: usize,
+}
+
+impl<R: ?Sized + Unpin> Unpin for ReadToString<'_, R> {}
+
+impl<'a, R: AsyncRead + ?Sized + Unpin> ReadToString<'a, R> {
+    pub(super) fn new(reader: &'a mut R, buf: &'a mut String) -> Self {
+        let start_len = buf.len();
+        Self {
+            reader,
+            bytes: mem::take(buf).into_bytes(),
+            buf,
+            start_len,
+        }
+    }
+}
+
+/// Moves `bytes` back into `buf`, dropping whatever follows the valid UTF-8
+/// read after `start_len`.
+fn restore(buf: &mut String, bytes: &mut Vec<u8>, start_len: usize) {
+    let valid = match str::from_utf8(&bytes[start_len..]) {
+        Ok(s) => s.len(),
+        Err(e) => e.valid_up_to(),
+    };
+    bytes.truncate(start_len + valid);
+    debug_assert!(buf.is_empty());
+    // SAFETY: the first `start_len` bytes came from a `String`, and the rest
+    // was just validated.
+    mem::swap(unsafe { buf.as_mut_vec() }, bytes);
+}
+
+impl<R: AsyncRead + ?Sized + Unpin> Future for ReadToString<'_, R> {
+    type Output = io::Result<usize>;
+
+    fn poll(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        let ret = ready!(read_to_end_internal(
+            Pin::new(&mut this.reader),
+            ctx,
+            &mut this.bytes,
+            this.start_len
+        ));
+        let ret = if str::from_utf8(&this.bytes[this.start_len..]).is_err() {
+            this.bytes.truncate(this.start_len);
+            ret.and_then(|_| {
+                Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "stream did not contain valid UTF-8",
+                ))
+            })
+        } else {
+            ret
+        };
+        restore(this.buf, &mut this.bytes, this.start_len);
+        Poll::Ready(ret)
+    }
+}
+
+impl<R: ?Sized> Drop for ReadToString<'_, R> {
+    fn drop(&mut self) {
+        // Nothing left to restore once the future completed.
+        if self.buf.is_empty() && self.bytes.len() >= self.start_len {
+            restore(self.buf, &mut self.bytes, self.start_len);
+        }
+    }
+}
@@ -0,0 +1,41 @@
+//! Combinators and utilities for working with `Future`s, `Stream`s and
+//! `Sink`s.
+//!
+//! Everything that boxes requires the `alloc` feature, and panic handling,
+//! I/O, framing and timers require `std`. The `std` feature is enabled by default.
+
+#![no_std]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+mod fns;
+
+#[doc(no_inline)]
+pub use futures_core::{join, select, select_biased, try_join};
+
+pub mod future;
+#[doc(no_inline)]
+pub use crate::future::{FutureExt, TryFutureExt};
+
+pub mod stream;
+#[doc(no_inline)]
+pub use crate::stream::{StreamExt, TryStreamExt};
+
+pub mod task;
+#[doc(no_inline)]
+pub use crate::task::{LocalSpawnExt, SpawnExt};
+
+#[cfg(feature = "std")]
+pub mod time;
+
+#[cfg(feature = "std")]
+pub mod io;
+#[cfg(feature = "std")]
+#[doc(no_inline)]
+pub use crate::io::{AsyncBufReadExt, AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+
+#[cfg(feature = "std")]
+pub mod codec;
@@ -0,0 +1,45 @@
+//! Asynchronous streams.
+//!
+//! This module contains the [`StreamExt`] trait, which adds adapters to every
+//! [`Stream`], the [`TryStreamExt`] trait for streams of `Result`s, the
+//! stream and future types those adapters return, and the [`iter`](iter())
+//! constructor.
+
+#[doc(no_inline)]
+pub use futures_core::stream::{FusedStream, Stream, TryStream};
+
+#[cfg(feature = "alloc")]
+#[doc(no_inline)]
+pub use futures_core::stream::{BoxStream, LocalBoxStream};
+
+#[allow(clippy::module_inception)]
+mod stream;
+pub use self::stream::{
+    Chain, Collect, Enumerate, Filter, FilterMap, Flatten, Fold, ForEach, Fuse, Map, Next, Skip,
+    StreamExt, Take, TakeWhile, Then, Zip,
+};
+
+#[cfg(feature = "std")]
+pub use self::stream::Timeout;
+
+mod try_stream;
+pub use self::try_stream::{
+    AndThen, IntoStream, MapErr, MapOk, TryCollect, TryFilter, TryFold, TryNext, TryStreamExt,
+};
+
+#[cfg(feature = "alloc")]
+pub use self::try_stream::{TryBufferUnordered, TryForEachConcurrent};
+
+mod iter;
+pub use self::iter::{iter, Iter};
+
+#[cfg(feature = "alloc")]
+pub mod futures_unordered;
+#[cfg(feature = "alloc")]
+#[doc(inline)]
+pub use self::futures_unordered::FuturesUnordered;
+
+#[cfg(feature = "alloc")]
+mod futures_ordered;
+#[cfg(feature = "alloc")]
+pub use self::futures_ordered::FuturesOrdered;
@@ -0,0 +1,71 @@
+use core::pin::Pin;
+use futures_core::ready;
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll};
+use pin_project_lite::pin_project;
+
+pin_project! {
+    /// Stream for the [`flatten`](super::StreamExt::flatten) method.
+    #[derive(Debug)]
+    #[must_use = "streams do nothing unless polled"]
+    pub struct Flatten<St, U> {
+        #[pin]
+        stream: St,
+        #[pin]
+        next: Option<U>,
+    }
+}
+
+impl<St, U> Flatten<St, U> {
+    pub(super) fn new(stream: St) -> Self {
+        Self { stream, next: None }
+    }
+}
+
+impl<St> FusedStream for Flatten<St, St::Item>
+where
+    St: FusedStream,
+    St::Item: Stream,
+{
+    fn is_terminated(&self) -> bool {
+        self.next.is_none() && self.stream.is_terminated()
+    }
+}
+
+impl<St> Stream for Flatten<St, St::Item>
+where
+    St: Stream,
+    St::Item: Stream,
+{
+    type Item = <St::Item as Stream>::Item;
+
+    fn poll_next(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+        Poll::Ready(loop {
+            if let Some(s) = this.next.as_mut().as_pin_mut() {
+                if let Some(item) = ready!(s.poll_next(ctx)) {
+                    break Some(item);
+                } else {
+                    this.next.set(None);
+                }
+            } else if let Some(s) = ready!(this.stream.as_mut().poll_next(ctx)) {
+                this.next.set(Some(s));
+            } else {
+                break None;
+            }
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (next_lower, next_upper) = match &self.next {
+            Some(next) => next.size_hint(),
+            None => (0, Some(0)),
+        };
+        // Nothing is known about streams that the outer stream has yet to
+        // yield, unless it is known to yield no more of them.
+        match self.stream.size_hint() {
+            (_, Some(0)) => (next_lower, next_upper),
+            _ => (next_lower, None),
+        }
+    }
+}
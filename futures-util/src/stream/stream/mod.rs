@@ -0,0 +1,489 @@
+//! Definition of the `StreamExt` combinator trait, which adds adapters to
+//! every stream.
+
+use core::pin::Pin;
+use futures_core::future::Future;
+use futures_core::stream::Stream;
+use futures_core::task::{Context, Poll};
+
+mod chain;
+pub use self::chain::Chain;
+
+mod collect;
+pub use self::collect::Collect;
+
+mod enumerate;
+pub use self::enumerate::Enumerate;
+
+mod filter;
+pub use self::filter::Filter;
+
+mod filter_map;
+pub use self::filter_map::FilterMap;
+
+mod flatten;
+pub use self::flatten::Flatten;
+
+mod fold;
+pub use self::fold::Fold;
+
+mod for_each;
+pub use self::for_each::ForEach;
+
+mod fuse;
+pub use self::fuse::Fuse;
+
+mod map;
+pub use self::map::Map;
+
+mod next;
+pub use self::next::Next;
+
+mod skip;
+pub use self::skip::Skip;
+
+mod take;
+pub use self::take::Take;
+
+mod take_while;
+pub use self::take_while::TakeWhile;
+
+mod then;
+pub use self::then::Then;
+
+#[cfg(feature = "std")]
+mod timeout;
+#[cfg(feature = "std")]
+pub use self::timeout::Timeout;
+
+mod zip;
+pub use self::zip::Zip;
+
+impl<T: ?Sized> StreamExt for T where T: Stream {}
+
+/// An extension trait for `Stream`s that provides a variety of convenient
+/// adapters.
+///
+/// Every adapter forwards `size_hint` with bounds adjusted for what it does
+/// to the item count, and implements `FusedStream` whenever that can be
+/// answered without polling.
+pub trait StreamExt: Stream {
+    /// Creates a future that resolves to the next item in the stream, or
+    /// `None` once the stream is exhausted.
+    fn next(&mut self) -> Next<'_, Self>
+    where
+        Self: Unpin,
+    {
+        Next::new(self)
+    }
+
+    /// Maps this stream's items to a different type.
+    fn map<T, F>(self, f: F) -> Map<Self, F>
+    where
+        F: FnMut(Self::Item) -> T,
+        Self: Sized,
+    {
+        Map::new(self, f)
+    }
+
+    /// Filters the items of this stream by an asynchronous predicate,
+    /// yielding only those for which it resolves to `true`.
+    fn filter<Fut, F>(self, f: F) -> Filter<Self, Fut, F>
+    where
+        F: FnMut(&Self::Item) -> Fut,
+        Fut: Future<Output = bool>,
+        Self: Sized,
+    {
+        Filter::new(self, f)
+    }
+
+    /// Filters the items of this stream while mapping them, yielding the
+    /// values for which the asynchronous closure resolves to `Some`.
+    fn filter_map<Fut, T, F>(self, f: F) -> FilterMap<Self, Fut, F>
+    where
+        F: FnMut(Self::Item) -> Fut,
+        Fut: Future<Output = Option<T>>,
+        Self: Sized,
+    {
+        FilterMap::new(self, f)
+    }
+
+    /// Computes from this stream's items new items of a different type using
+    /// an asynchronous closure.
+    fn then<Fut, F>(self, f: F) -> Then<Self, Fut, F>
+    where
+        F: FnMut(Self::Item) -> Fut,
+        Fut: Future,
+        Self: Sized,
+    {
+        Then::new(self, f)
+    }
+
+    /// Creates a new stream of at most `n` items of the underlying stream.
+    fn take(self, n: usize) -> Take<Self>
+    where
+        Self: Sized,
+    {
+        Take::new(self, n)
+    }
+
+    /// Creates a new stream which skips the first `n` items of the
+    /// underlying stream.
+    fn skip(self, n: usize) -> Skip<Self>
+    where
+        Self: Sized,
+    {
+        Skip::new(self, n)
+    }
+
+    /// Takes items from this stream while the asynchronous predicate resolves
+    /// to `true`. The stream ends at the first item it rejects.
+    fn take_while<Fut, F>(self, f: F) -> TakeWhile<Self, Fut, F>
+    where
+        F: FnMut(&Self::Item) -> Fut,
+        Fut: Future<Output = bool>,
+        Self: Sized,
+    {
+        TakeWhile::new(self, f)
+    }
+
+    /// Adapter for chaining two streams: all of this stream's items are
+    /// yielded first, then all of `other`'s.
+    fn chain<St>(self, other: St) -> Chain<Self, St>
+    where
+        St: Stream<Item = Self::Item>,
+        Self: Sized,
+    {
+        Chain::new(self, other)
+    }
+
+    /// Zips two streams together into a stream of pairs, ending as soon as
+    /// either stream ends.
+    fn zip<St>(self, other: St) -> Zip<Self, St>
+    where
+        St: Stream,
+        Self: Sized,
+    {
+        Zip::new(self, other)
+    }
+
+    /// Creates a stream which gives the current iteration count as well as
+    /// the next value.
+    fn enumerate(self) -> Enumerate<Self>
+    where
+        Self: Sized,
+    {
+        Enumerate::new(self)
+    }
+
+    /// Executes an accumulating asynchronous computation over the stream,
+    /// resolving to the final value once the stream ends.
+    fn fold<T, Fut, F>(self, init: T, f: F) -> Fold<Self, Fut, T, F>
+    where
+        F: FnMut(T, Self::Item) -> Fut,
+        Fut: Future<Output = T>,
+        Self: Sized,
+    {
+        Fold::new(self, f, init)
+    }
+
+    /// Transforms the stream into a collection, resolving once the stream
+    /// ends.
+    fn collect<C: Default + Extend<Self::Item>>(self) -> Collect<Self, C>
+    where
+        Self: Sized,
+    {
+        Collect::new(self)
+    }
+
+    /// Runs the asynchronous closure for each item of the stream in turn,
+    /// resolving once the stream ends.
+    fn for_each<Fut, F>(self, f: F) -> ForEach<Self, Fut, F>
+    where
+        F: FnMut(Self::Item) -> Fut,
+        Fut: Future<Output = ()>,
+        Self: Sized,
+    {
+        ForEach::new(self, f)
+    }
+
+    /// Flattens a stream of streams into just one continuous stream.
+    fn flatten(self) -> Flatten<Self, Self::Item>
+    where
+        Self::Item: Stream,
+        Self: Sized,
+    {
+        Flatten::new(self)
+    }
+
+    /// Fuse a stream such that `poll_next` will never again be called once it
+    /// has finished.
+    ///
+    /// The returned stream is a `FusedStream` and keeps returning
+    /// `Poll::Ready(None)` after the underlying stream ended.
+    fn fuse(self) -> Fuse<Self>
+    where
+        Self: Sized,
+    {
+        Fuse::new(self)
+    }
+
+    /// Requires every item of this stream to arrive within `dur` of the
+    /// previous one, or of the first poll.
+    ///
+    /// Items are yielded as `Ok`. Whenever `dur` passes without one, an
+    /// [`Elapsed`](crate::time::Elapsed) error is yielded instead and the
+    /// stream keeps waiting, for another `dur` before the next error.
+    #[cfg(feature = "std")]
+    fn timeout(self, dur: core::time::Duration) -> Timeout<Self>
+    where
+        Self: Sized,
+    {
+        Timeout::new(self, dur)
+    }
+
+    /// A convenience method for calling [`Stream::poll_next`] on `Unpin`
+    /// stream types.
+    fn poll_next_unpin(&mut self, ctx: &mut Context<'_>) -> Poll<Option<Self::Item>>
+    where
+        Self: Unpin,
+    {
+        Pin::new(self).poll_next(ctx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StreamExt;
+    use crate::future::FutureExt;
+    use crate::stream::iter;
+    use core::future::ready;
+    use core::pin::Pin;
+    use core::task::{Context, Poll, Waker};
+    use futures_core::stream::{FusedStream, Stream};
+    use std::vec;
+    use std::vec::Vec;
+
+    /// Yields `Pending` once before every item, and reports an unknown
+    /// length.
+    struct Stutter<I> {
+        iter: I,
+        ready: bool,
+    }
+
+    impl<I: Iterator + Unpin> Stream for Stutter<I> {
+        type Item = I::Item;
+
+        fn poll_next(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Option<I::Item>> {
+            self.ready = !self.ready;
+            if !self.ready {
+                Poll::Ready(self.iter.next())
+            } else {
+                ctx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        }
+    }
+
+    fn stutter<I: IntoIterator>(i: I) -> Stutter<I::IntoIter> {
+        Stutter {
+            iter: i.into_iter(),
+            ready: false,
+        }
+    }
+
+    fn drain<S: Stream + Unpin>(mut stream: S) -> Vec<S::Item> {
+        let mut ctx = Context::from_waker(Waker::noop());
+        let mut out = Vec::new();
+        loop {
+            match stream.poll_next_unpin(&mut ctx) {
+                Poll::Ready(Some(item)) => out.push(item),
+                Poll::Ready(None) => return out,
+                Poll::Pending => {}
+            }
+        }
+    }
+
+    #[test]
+    fn next_and_fuse() {
+        let mut stream = iter(1..=2).fuse();
+        assert_eq!(stream.next().now_or_never(), Some(Some(1)));
+        assert_eq!(stream.next().now_or_never(), Some(Some(2)));
+        assert!(!stream.is_terminated());
+        assert_eq!(stream.next().now_or_never(), Some(None));
+        assert!(stream.is_terminated());
+        assert_eq!(stream.next().now_or_never(), Some(None));
+        assert_eq!(stream.size_hint(), (0, Some(0)));
+    }
+
+    #[test]
+    fn map_filter_then() {
+        let stream = iter(1..=6)
+            .map(|x| x * 10)
+            .filter(|x| ready(*x % 20 == 0))
+            .filter_map(|x| ready((x != 40).then_some(x)))
+            .then(|x| ready(x + 1));
+        assert_eq!(stream.size_hint(), (0, Some(6)));
+        assert_eq!(drain(stream), vec![21, 61]);
+
+        let stream = stutter(1..=4).filter(|x| ready(x % 2 == 1));
+        assert_eq!(drain(stream), vec![1, 3]);
+    }
+
+    #[test]
+    fn take_skip_take_while() {
+        let stream = iter(1..=10).skip(2).take(3);
+        assert_eq!(stream.size_hint(), (3, Some(3)));
+        assert_eq!(drain(stream), vec![3, 4, 5]);
+
+        let stream = iter(1..3).take(5);
+        assert_eq!(stream.size_hint(), (2, Some(2)));
+        assert_eq!(iter(1..3).skip(5).size_hint(), (0, Some(0)));
+
+        let mut stream = iter(1..=10).take_while(|x| ready(*x < 3)).fuse();
+        assert_eq!(stream.size_hint(), (0, Some(10)));
+        assert_eq!(stream.next().now_or_never(), Some(Some(1)));
+        assert_eq!(stream.next().now_or_never(), Some(Some(2)));
+        assert_eq!(stream.next().now_or_never(), Some(None));
+        assert_eq!(stream.size_hint(), (0, Some(0)));
+    }
+
+    #[test]
+    fn take_is_terminated_without_polling() {
+        let stream = Never.fuse().take(0);
+        assert!(stream.is_terminated());
+        assert_eq!(stream.size_hint(), (0, Some(0)));
+    }
+
+    #[test]
+    fn chain_zip_enumerate() {
+        let stream = iter(1..=2).chain(stutter(3..=4));
+        assert_eq!(stream.size_hint(), (2, None));
+        assert_eq!(drain(stream), vec![1, 2, 3, 4]);
+
+        let stream = iter(1..=3).zip(stutter("abcd".chars()));
+        assert_eq!(stream.size_hint(), (0, Some(3)));
+        assert_eq!(drain(stream), vec![(1, 'a'), (2, 'b'), (3, 'c')]);
+
+        let mut stream = iter(0..0).zip(Never);
+        assert_eq!(stream.next().now_or_never(), Some(None));
+        assert!(stream.is_terminated());
+
+        let stream = iter(vec!['x', 'y']).enumerate();
+        assert_eq!(stream.size_hint(), (2, Some(2)));
+        assert_eq!(drain(stream), vec![(0, 'x'), (1, 'y')]);
+    }
+
+    #[test]
+    fn flatten() {
+        let stream = iter(vec![iter(1..=2), iter(3..=3)]).flatten();
+        assert_eq!(stream.size_hint(), (0, None));
+        assert_eq!(drain(stream), vec![1, 2, 3]);
+
+        let mut stream = iter(vec![iter(1..=3)]).flatten();
+        assert_eq!(stream.next().now_or_never(), Some(Some(1)));
+        assert_eq!(stream.size_hint(), (2, Some(2)));
+    }
+
+    #[test]
+    fn consumers() {
+        let sum = iter(1..=4).fold(0, |acc, x| ready(acc + x));
+        assert_eq!(sum.now_or_never(), Some(10));
+
+        let all: Vec<_> = stutter(1..=3).collect::<Vec<_>>().wait();
+        assert_eq!(all, vec![1, 2, 3]);
+
+        let mut seen = Vec::new();
+        iter(1..=3)
+            .for_each(|x| {
+                seen.push(x);
+                ready(())
+            })
+            .now_or_never()
+            .unwrap();
+        assert_eq!(seen, vec![1, 2, 3]);
+    }
+
+    /// Never yields an item.
+    struct Never;
+
+    impl Stream for Never {
+        type Item = ();
+
+        fn poll_next(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<()>> {
+            Poll::Pending
+        }
+    }
+
+    #[test]
+    fn select_over_fused_streams() {
+        let mut a = Stutter {
+            iter: vec![1, 2].into_iter(),
+            ready: false,
+        }
+        .fuse();
+        let mut b = iter(vec![10, 20, 30]).fuse();
+        let sums = async {
+            let (mut sum_a, mut sum_b) = (0, 0);
+            loop {
+                crate::select! {
+                    x = a.next() => sum_a += x.unwrap_or(0),
+                    y = b.next() => sum_b += y.unwrap_or(0),
+                    complete => break (sum_a, sum_b),
+                }
+            }
+        };
+        assert_eq!(sums.wait(), (3, 60));
+    }
+
+    #[test]
+    fn timeout_between_items() {
+        use crate::time::{Elapsed, MockClock};
+        use core::time::Duration;
+
+        let clock = MockClock::new();
+        let _guard = clock.enter();
+        let ms = Duration::from_millis(1);
+        let mut ctx = Context::from_waker(Waker::noop());
+        let (tx, rx) = futures_channel::mpsc::unbounded::<u8>();
+        let mut stream = rx.timeout(10 * ms);
+
+        assert!(stream.poll_next_unpin(&mut ctx).is_pending());
+        clock.advance(9 * ms);
+        tx.unbounded_send(1).unwrap();
+        assert_eq!(stream.next().now_or_never(), Some(Some(Ok(1))));
+
+        // The deadline restarted with the item.
+        clock.advance(9 * ms);
+        assert!(stream.poll_next_unpin(&mut ctx).is_pending());
+        clock.advance(ms);
+        assert_eq!(
+            stream.next().now_or_never(),
+            Some(Some(Err(Elapsed::new())))
+        );
+        assert!(stream.poll_next_unpin(&mut ctx).is_pending());
+        clock.advance(10 * ms);
+        assert_eq!(
+            stream.next().now_or_never(),
+            Some(Some(Err(Elapsed::new())))
+        );
+
+        tx.close_channel();
+        assert_eq!(stream.next().now_or_never(), Some(None));
+        assert!(stream.is_terminated());
+    }
+
+    trait Wait: core::future::Future + Sized {
+        fn wait(self) -> Self::Output {
+            let mut ctx = Context::from_waker(Waker::noop());
+            let mut this = core::pin::pin!(self);
+            loop {
+                if let Poll::Ready(out) = this.as_mut().poll(&mut ctx) {
+                    return out;
+                }
+            }
+        }
+    }
+
+    impl<F: core::future::Future> Wait for F {}
+}
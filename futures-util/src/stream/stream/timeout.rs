@@ -0,0 +1,67 @@
+use crate::time::{Delay, Elapsed};
+use core::pin::Pin;
+use core::time::Duration;
+use futures_core::future::Future;
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll};
+use pin_project_lite::pin_project;
+
+pin_project! {
+    /// Stream for the [`timeout`](super::StreamExt::timeout) method.
+    #[derive(Debug)]
+    #[must_use = "streams do nothing unless polled"]
+    pub struct Timeout<St> {
+        #[pin]
+        stream: St,
+        duration: Duration,
+        // Armed on the first poll.
+        delay: Option<Delay>,
+    }
+}
+
+impl<St: Stream> Timeout<St> {
+    pub(super) fn new(stream: St, duration: Duration) -> Self {
+        Self {
+            stream,
+            duration,
+            delay: None,
+        }
+    }
+}
+
+impl<St: Stream> Stream for Timeout<St> {
+    type Item = Result<St::Item, Elapsed>;
+
+    fn poll_next(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+        let duration = *this.duration;
+        let delay = this.delay.get_or_insert_with(|| Delay::new(duration));
+
+        match this.stream.poll_next(ctx) {
+            Poll::Ready(Some(item)) => {
+                delay.reset_after(duration);
+                Poll::Ready(Some(Ok(item)))
+            }
+            Poll::Ready(None) => Poll::Ready(None),
+            Poll::Pending => {
+                if Pin::new(&mut *delay).poll(ctx).is_pending() {
+                    return Poll::Pending;
+                }
+                // Keep going: the next error needs another full timeout.
+                delay.reset_after(duration);
+                Poll::Ready(Some(Err(Elapsed::new())))
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // Any number of timeouts may come in between the items.
+        (self.stream.size_hint().0, None)
+    }
+}
+
+impl<St: FusedStream> FusedStream for Timeout<St> {
+    fn is_terminated(&self) -> bool {
+        self.stream.is_terminated()
+    }
+}
@@ -0,0 +1,436 @@
+//! Definition of the `TryStreamExt` combinator trait, which adds adapters to
+//! every stream of `Result`s, allowing for short-circuiting computations.
+
+use core::pin::Pin;
+use futures_core::future::{Future, TryFuture};
+use futures_core::stream::{FusedStream, Stream, TryStream};
+use futures_core::task::{Context, Poll};
+use pin_project_lite::pin_project;
+
+use crate::fns::{map_err_fn, map_ok_fn, MapErrFn, MapOkFn};
+use crate::stream::Map;
+
+mod and_then;
+pub use self::and_then::AndThen;
+
+mod into_stream;
+pub use self::into_stream::IntoStream;
+
+mod try_collect;
+pub use self::try_collect::TryCollect;
+
+mod try_filter;
+pub use self::try_filter::TryFilter;
+
+mod try_fold;
+pub use self::try_fold::TryFold;
+
+mod try_next;
+pub use self::try_next::TryNext;
+
+#[cfg(feature = "alloc")]
+mod try_buffer_unordered;
+#[cfg(feature = "alloc")]
+pub use self::try_buffer_unordered::TryBufferUnordered;
+
+#[cfg(feature = "alloc")]
+mod try_for_each_concurrent;
+#[cfg(feature = "alloc")]
+pub use self::try_for_each_concurrent::TryForEachConcurrent;
+
+// `map_ok` and `map_err` are thin wrappers around the shared `Map` adapter,
+// so that the composed type doesn't leak into the public API.
+macro_rules! delegate_stream {
+    ($(#[$attr:meta])* $name:ident<$($param:ident),*>($inner:ty)) => {
+        pin_project! {
+            $(#[$attr])*
+            #[derive(Debug)]
+            #[must_use = "streams do nothing unless polled"]
+            pub struct $name<$($param),*> {
+                #[pin]
+                inner: $inner,
+            }
+        }
+
+        impl<$($param),*> Stream for $name<$($param),*>
+        where
+            $inner: Stream,
+        {
+            type Item = <$inner as Stream>::Item;
+
+            #[inline]
+            fn poll_next(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+                self.project().inner.poll_next(ctx)
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                self.inner.size_hint()
+            }
+        }
+
+        impl<$($param),*> FusedStream for $name<$($param),*>
+        where
+            $inner: FusedStream,
+        {
+            fn is_terminated(&self) -> bool {
+                self.inner.is_terminated()
+            }
+        }
+    };
+}
+
+delegate_stream!(
+    /// Stream for the [`map_ok`](TryStreamExt::map_ok) method.
+    MapOk<St, F>(Map<IntoStream<St>, MapOkFn<F>>)
+);
+
+delegate_stream!(
+    /// Stream for the [`map_err`](TryStreamExt::map_err) method.
+    MapErr<St, F>(Map<IntoStream<St>, MapErrFn<F>>)
+);
+
+impl<S: ?Sized + TryStream> TryStreamExt for S {}
+
+/// Adapters specific to `Result`-returning streams.
+///
+/// Adapters that run closures on success values pass errors through
+/// untouched, and consumers short-circuit: they resolve to the first error
+/// the stream or one of their own futures produces.
+pub trait TryStreamExt: TryStream {
+    /// Creates a future that attempts to resolve the next item in the stream.
+    ///
+    /// If an error is encountered before the next item, the error is
+    /// returned instead.
+    fn try_next(&mut self) -> TryNext<'_, Self>
+    where
+        Self: Unpin,
+    {
+        TryNext::new(self)
+    }
+
+    /// Wraps the current stream in a new stream which maps the success value
+    /// using the provided closure.
+    fn map_ok<T, F>(self, f: F) -> MapOk<Self, F>
+    where
+        F: FnMut(Self::Ok) -> T,
+        Self: Sized,
+    {
+        MapOk {
+            inner: Map::new(IntoStream::new(self), map_ok_fn(f)),
+        }
+    }
+
+    /// Wraps the current stream in a new stream which maps the error value
+    /// using the provided closure.
+    fn map_err<E, F>(self, f: F) -> MapErr<Self, F>
+    where
+        F: FnMut(Self::Error) -> E,
+        Self: Sized,
+    {
+        MapErr {
+            inner: Map::new(IntoStream::new(self), map_err_fn(f)),
+        }
+    }
+
+    /// Chain on a computation for when a value is ready, passing the
+    /// successful result to the provided closure `f`.
+    ///
+    /// The future returned by `f` is polled to completion before the next
+    /// item is pulled from the stream; its output, success or error, becomes
+    /// the next item.
+    fn and_then<Fut, F>(self, f: F) -> AndThen<Self, Fut, F>
+    where
+        F: FnMut(Self::Ok) -> Fut,
+        Fut: TryFuture<Error = Self::Error>,
+        Self: Sized,
+    {
+        AndThen::new(self, f)
+    }
+
+    /// Filters the success values of this stream by an asynchronous
+    /// predicate. Errors are passed through untouched.
+    fn try_filter<Fut, F>(self, f: F) -> TryFilter<Self, Fut, F>
+    where
+        Fut: Future<Output = bool>,
+        F: FnMut(&Self::Ok) -> Fut,
+        Self: Sized,
+    {
+        TryFilter::new(self, f)
+    }
+
+    /// Attempt to transform a stream into a collection, returning a future
+    /// representing the result of that computation.
+    ///
+    /// The future resolves to the first error the stream yields, if any.
+    fn try_collect<C: Default + Extend<Self::Ok>>(self) -> TryCollect<Self, C>
+    where
+        Self: Sized,
+    {
+        TryCollect::new(self)
+    }
+
+    /// Attempt to execute an accumulating asynchronous computation over a
+    /// stream, collecting all the values into one final result.
+    ///
+    /// The future resolves to the first error produced by the stream or by
+    /// `f`.
+    fn try_fold<T, Fut, F>(self, init: T, f: F) -> TryFold<Self, Fut, T, F>
+    where
+        F: FnMut(T, Self::Ok) -> Fut,
+        Fut: TryFuture<Ok = T, Error = Self::Error>,
+        Self: Sized,
+    {
+        TryFold::new(self, f, init)
+    }
+
+    /// Runs `f` for each success value of the stream, with at most `limit`
+    /// of the futures it returns running concurrently.
+    ///
+    /// `None` or `Some(0)` means no limit. The returned future fails fast:
+    /// on the first error, whether from the stream or from one of the
+    /// futures, it stops pulling from the stream, drops the futures still in
+    /// flight, and resolves to that error.
+    #[cfg(feature = "alloc")]
+    fn try_for_each_concurrent<Fut, F>(
+        self,
+        limit: impl Into<Option<usize>>,
+        f: F,
+    ) -> TryForEachConcurrent<Self, Fut, F>
+    where
+        F: FnMut(Self::Ok) -> Fut,
+        Fut: Future<Output = Result<(), Self::Error>>,
+        Self: Sized,
+    {
+        TryForEachConcurrent::new(self, limit.into(), f)
+    }
+
+    /// Attempt to execute several futures from a stream concurrently,
+    /// yielding their results in the order they complete.
+    ///
+    /// This stream's success values must be futures themselves; at most `n`
+    /// of them are kept in flight, and `0` means no limit. The returned
+    /// stream fails fast: the first error, whether from this stream or from
+    /// one of the futures, is yielded, the futures still in flight are
+    /// dropped, and the stream then ends.
+    #[cfg(feature = "alloc")]
+    fn try_buffer_unordered(self, n: usize) -> TryBufferUnordered<Self>
+    where
+        Self::Ok: TryFuture<Error = Self::Error>,
+        Self: Sized,
+    {
+        TryBufferUnordered::new(self, n)
+    }
+
+    /// Wraps a [`TryStream`] into a type that implements [`Stream`].
+    fn into_stream(self) -> IntoStream<Self>
+    where
+        Self: Sized,
+    {
+        IntoStream::new(self)
+    }
+
+    /// A convenience method for calling [`TryStream::try_poll_next`] on
+    /// `Unpin` stream types.
+    fn try_poll_next_unpin(
+        &mut self,
+        ctx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Self::Ok, Self::Error>>>
+    where
+        Self: Unpin,
+    {
+        Pin::new(self).try_poll_next(ctx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TryStreamExt;
+    use crate::future::FutureExt;
+    use crate::stream::{iter, StreamExt};
+    use core::cell::Cell;
+    use core::future::{ready, Future};
+    use core::pin::Pin;
+    use core::task::{Context, Poll, Waker};
+    use futures_core::future::FusedFuture;
+    use futures_core::stream::{FusedStream, Stream};
+    use std::rc::Rc;
+    use std::vec;
+    use std::vec::Vec;
+
+    /// Tracks how many `Job`s are alive, and the most that ever were.
+    #[derive(Default)]
+    struct Gauge {
+        live: Cell<usize>,
+        peak: Cell<usize>,
+    }
+
+    /// Completes with `result` after being polled `polls` times, counting
+    /// itself as alive in the gauge until dropped.
+    struct Job<T> {
+        polls: usize,
+        result: Option<Result<T, u32>>,
+        gauge: Rc<Gauge>,
+    }
+
+    impl<T> Job<T> {
+        fn new(polls: usize, result: Result<T, u32>, gauge: &Rc<Gauge>) -> Self {
+            gauge.live.set(gauge.live.get() + 1);
+            gauge.peak.set(gauge.peak.get().max(gauge.live.get()));
+            Job {
+                polls,
+                result: Some(result),
+                gauge: gauge.clone(),
+            }
+        }
+    }
+
+    impl<T> Drop for Job<T> {
+        fn drop(&mut self) {
+            self.gauge.live.set(self.gauge.live.get() - 1);
+        }
+    }
+
+    impl<T: Unpin> Future for Job<T> {
+        type Output = Result<T, u32>;
+
+        fn poll(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
+            if self.polls == 0 {
+                Poll::Ready(self.result.take().unwrap())
+            } else {
+                self.polls -= 1;
+                ctx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        }
+    }
+
+    fn wait<F: Future>(fut: F) -> F::Output {
+        let mut ctx = Context::from_waker(Waker::noop());
+        let mut fut = core::pin::pin!(fut);
+        loop {
+            if let Poll::Ready(out) = fut.as_mut().poll(&mut ctx) {
+                return out;
+            }
+        }
+    }
+
+    #[test]
+    fn map_and_then_filter() {
+        let stream = iter(vec![Ok(1), Err(2), Ok(3), Ok(4)])
+            .map_ok(|x: u32| x * 10)
+            .map_err(|e: u32| e + 100)
+            .and_then(|x| ready(if x == 30 { Err(x) } else { Ok(x) }))
+            .try_filter(|x| ready(*x != 40));
+        let items: Vec<_> = wait(stream.collect());
+        assert_eq!(items, vec![Ok(10), Err(102), Err(30)]);
+    }
+
+    #[test]
+    fn consumers_short_circuit() {
+        let mut stream = iter(vec![Ok::<u32, u32>(1), Err(2), Ok(3)]);
+        assert_eq!(stream.try_next().now_or_never(), Some(Ok(Some(1))));
+        assert_eq!(stream.try_next().now_or_never(), Some(Err(2)));
+
+        let all = iter(vec![Ok::<u32, u32>(1), Ok(2)]).try_collect::<Vec<_>>();
+        assert_eq!(wait(all), Ok(vec![1, 2]));
+        let first_err = iter(vec![Ok::<u32, u32>(1), Err(5), Err(6)]).try_collect::<Vec<_>>();
+        assert_eq!(wait(first_err), Err(5));
+
+        let sum = iter(vec![Ok::<u32, u32>(1), Ok(2)]).try_fold(0, |a, x| ready(Ok(a + x)));
+        assert_eq!(wait(sum), Ok(3));
+        let failed = iter(vec![Ok::<u32, u32>(1), Ok(2)])
+            .try_fold(0, |a, x| ready(if x == 2 { Err(a) } else { Ok(a + x) }));
+        assert_eq!(wait(failed), Err(1));
+    }
+
+    #[test]
+    fn for_each_concurrent_respects_limit() {
+        let gauge = Rc::new(Gauge::default());
+        let done = Cell::new(0);
+        let fut = iter((0..10).map(Ok::<usize, u32>)).try_for_each_concurrent(3, |i| {
+            let job = Job::new(i % 4, Ok(()), &gauge);
+            let done = &done;
+            job.map(move |res| {
+                done.set(done.get() + 1);
+                res
+            })
+        });
+        let mut fut = core::pin::pin!(fut);
+        assert!(!fut.is_terminated());
+        assert_eq!(wait(fut.as_mut()), Ok(()));
+        assert!(fut.is_terminated());
+        assert_eq!(done.get(), 10);
+        assert_eq!(gauge.peak.get(), 3);
+    }
+
+    #[test]
+    fn for_each_concurrent_fails_fast() {
+        let gauge = Rc::new(Gauge::default());
+        let started = Cell::new(0);
+        let fut = iter((0..10).map(Ok::<usize, u32>)).try_for_each_concurrent(4, |i| {
+            started.set(started.get() + 1);
+            // Job 1 fails right away while the others are still running.
+            if i == 1 {
+                Job::new(0, Err(7), &gauge)
+            } else {
+                Job::new(100, Ok(()), &gauge)
+            }
+        });
+        assert_eq!(wait(fut), Err(7));
+        assert_eq!(gauge.live.get(), 0);
+        assert!(started.get() <= 4);
+
+        let fut = iter(vec![Ok(1), Err(9), Ok(2)])
+            .try_for_each_concurrent(None, |_| Job::new(100, Ok(()), &gauge));
+        assert_eq!(wait(fut), Err(9));
+        assert_eq!(gauge.live.get(), 0);
+    }
+
+    #[test]
+    fn buffer_unordered_yields_in_completion_order() {
+        let gauge = Rc::new(Gauge::default());
+        let jobs: Vec<Result<_, u32>> = vec![
+            Ok(Job::new(6, Ok('a'), &gauge)),
+            Ok(Job::new(0, Ok('b'), &gauge)),
+            Ok(Job::new(2, Ok('c'), &gauge)),
+        ];
+        let stream = iter(jobs).try_buffer_unordered(2);
+        let out: Vec<_> = wait(stream.collect());
+        assert_eq!(out, vec![Ok('b'), Ok('c'), Ok('a')]);
+    }
+
+    #[test]
+    fn buffer_unordered_fails_fast() {
+        let gauge = Rc::new(Gauge::default());
+        let jobs: Vec<Result<_, u32>> = vec![
+            Ok(Job::new(100, Ok(1), &gauge)),
+            Ok(Job::new(1, Err(3), &gauge)),
+            Ok(Job::new(0, Ok(2), &gauge)),
+        ];
+        let mut stream = iter(jobs).try_buffer_unordered(2);
+        assert_eq!(wait(stream.next()), Some(Err(3)));
+        assert!(stream.is_terminated());
+        // The job still running was dropped; the one never started is only
+        // dropped along with the stream.
+        assert_eq!(gauge.live.get(), 1);
+        assert_eq!(wait(stream.next()), None);
+        drop(stream);
+        assert_eq!(gauge.live.get(), 0);
+
+        let mut stream =
+            iter(vec![Ok(Job::new(100, Ok(1), &gauge)), Err(4)]).try_buffer_unordered(2);
+        assert_eq!(wait(stream.next()), Some(Err(4)));
+        assert_eq!(gauge.live.get(), 0);
+        assert_eq!(stream.size_hint(), (0, Some(0)));
+    }
+
+    #[test]
+    fn buffer_unordered_zero_means_no_limit() {
+        let gauge = Rc::new(Gauge::default());
+        let jobs: Vec<Result<_, u32>> =
+            (0..5).map(|i| Ok(Job::new(5 - i, Ok(i), &gauge))).collect();
+        let out: Vec<_> = wait(iter(jobs).try_buffer_unordered(0).collect());
+        assert_eq!(out, vec![Ok(4), Ok(3), Ok(2), Ok(1), Ok(0)]);
+        assert_eq!(gauge.peak.get(), 5);
+    }
+}
@@ -0,0 +1,116 @@
+use super::IntoStream;
+use crate::future::{IntoFuture, TryFutureExt};
+use crate::stream::{Fuse, FuturesUnordered, StreamExt};
+use core::num::NonZeroUsize;
+use core::pin::Pin;
+use futures_core::future::TryFuture;
+use futures_core::stream::{FusedStream, Stream, TryStream};
+use futures_core::task::{Context, Poll};
+use pin_project_lite::pin_project;
+
+pin_project! {
+    /// Stream for the
+    /// [`try_buffer_unordered`](super::TryStreamExt::try_buffer_unordered) method.
+    #[project = TryBufferUnorderedProj]
+    #[derive(Debug)]
+    #[must_use = "streams do nothing unless polled"]
+    pub struct TryBufferUnordered<St>
+        where St: TryStream
+    {
+        #[pin]
+        stream: Fuse<IntoStream<St>>,
+        in_progress_queue: FuturesUnordered<IntoFuture<St::Ok>>,
+        max: Option<NonZeroUsize>,
+        failed: bool,
+    }
+}
+
+impl<St> TryBufferUnordered<St>
+where
+    St: TryStream,
+    St::Ok: TryFuture,
+{
+    pub(super) fn new(stream: St, n: usize) -> Self {
+        Self {
+            stream: IntoStream::new(stream).fuse(),
+            in_progress_queue: FuturesUnordered::new(),
+            // A limit of 0 means no limit.
+            max: NonZeroUsize::new(n),
+            failed: false,
+        }
+    }
+}
+
+impl<St> Stream for TryBufferUnordered<St>
+where
+    St: TryStream,
+    St::Ok: TryFuture<Error = St::Error>,
+{
+    type Item = Result<<St::Ok as TryFuture>::Ok, St::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+        if *this.failed {
+            return Poll::Ready(None);
+        }
+
+        // Fill the queue of in-flight futures up to `max`, propagating errors
+        // from the stream immediately.
+        while this
+            .max
+            .is_none_or(|max| max.get() > this.in_progress_queue.len())
+        {
+            match this.stream.as_mut().poll_next(ctx) {
+                Poll::Ready(Some(Ok(fut))) => this.in_progress_queue.push(fut.into_future()),
+                Poll::Ready(Some(Err(e))) => return Poll::Ready(Some(Err(this.fail(e)))),
+                Poll::Ready(None) | Poll::Pending => break,
+            }
+        }
+
+        match this.in_progress_queue.poll_next_unpin(ctx) {
+            Poll::Ready(Some(Err(e))) => return Poll::Ready(Some(Err(this.fail(e)))),
+            x @ (Poll::Pending | Poll::Ready(Some(_))) => return x,
+            Poll::Ready(None) => {}
+        }
+
+        if this.stream.is_done() {
+            Poll::Ready(None)
+        } else {
+            Poll::Pending
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.failed {
+            return (0, Some(0));
+        }
+        let queue_len = self.in_progress_queue.len();
+        let (lower, upper) = self.stream.size_hint();
+        let upper = upper.and_then(|x| x.checked_add(queue_len));
+        // Any item may turn out to be an error that ends the stream.
+        (usize::from(lower.saturating_add(queue_len) > 0), upper)
+    }
+}
+
+impl<St> FusedStream for TryBufferUnordered<St>
+where
+    St: TryStream,
+    St::Ok: TryFuture<Error = St::Error>,
+{
+    fn is_terminated(&self) -> bool {
+        self.failed || (self.stream.is_done() && self.in_progress_queue.is_empty())
+    }
+}
+
+impl<St> TryBufferUnorderedProj<'_, St>
+where
+    St: TryStream,
+{
+    /// Fail fast: the stream ends after yielding `e`, and the futures in
+    /// flight are dropped.
+    fn fail<E>(&mut self, e: E) -> E {
+        *self.failed = true;
+        self.in_progress_queue.clear();
+        e
+    }
+}
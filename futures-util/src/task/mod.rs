@@ -0,0 +1,20 @@
+//! Tools for working with tasks.
+//!
+//! This module re-exports the task types from `futures_core` and adds the
+//! [`SpawnExt`] and [`LocalSpawnExt`] traits, which spawn plain futures on
+//! any [`Spawn`] or [`LocalSpawn`] executor.
+
+#[doc(no_inline)]
+pub use futures_core::task::{
+    Context, FutureObj, LocalFutureObj, LocalSpawn, Poll, Spawn, SpawnError, UnsafeFutureObj, Waker,
+};
+
+#[doc(no_inline)]
+pub use futures_core::task::{noop_context, noop_waker, noop_waker_ref};
+
+#[cfg(feature = "alloc")]
+#[doc(no_inline)]
+pub use futures_core::task::{waker, waker_ref, ArcWake, WakerRef};
+
+mod spawn;
+pub use self::spawn::{LocalSpawnExt, SpawnExt};
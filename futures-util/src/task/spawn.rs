@@ -0,0 +1,191 @@
+use futures_core::task::{LocalSpawn, Spawn};
+
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+#[cfg(feature = "alloc")]
+use futures_core::future::Future;
+#[cfg(feature = "alloc")]
+use futures_core::task::{FutureObj, LocalFutureObj, SpawnError};
+
+#[cfg(feature = "std")]
+use crate::future::{FutureExt, RemoteHandle};
+
+impl<Sp: ?Sized> SpawnExt for Sp where Sp: Spawn {}
+impl<Sp: ?Sized> LocalSpawnExt for Sp where Sp: LocalSpawn {}
+
+/// Extension trait for [`Spawn`].
+pub trait SpawnExt: Spawn {
+    /// Spawns a task that polls the given future with output `()` to
+    /// completion.
+    ///
+    /// This is a convenience for boxing the future into a [`FutureObj`] and
+    /// calling [`Spawn::spawn_obj`].
+    #[cfg(feature = "alloc")]
+    fn spawn<Fut>(&self, future: Fut) -> Result<(), SpawnError>
+    where
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.spawn_obj(FutureObj::new(Box::new(future)))
+    }
+
+    /// Spawns a task that polls the given future to completion and returns a
+    /// handle to its output.
+    ///
+    /// The returned [`RemoteHandle`] resolves to the future's output.
+    /// Dropping it cancels the task; call [`RemoteHandle::forget`] to detach
+    /// the task instead.
+    #[cfg(feature = "std")]
+    fn spawn_with_handle<Fut>(&self, future: Fut) -> Result<RemoteHandle<Fut::Output>, SpawnError>
+    where
+        Fut: Future + Send + 'static,
+        Fut::Output: Send,
+    {
+        let (future, handle) = future.remote_handle();
+        self.spawn(future)?;
+        Ok(handle)
+    }
+}
+
+/// Extension trait for [`LocalSpawn`].
+pub trait LocalSpawnExt: LocalSpawn {
+    /// Spawns a task that polls the given future with output `()` to
+    /// completion.
+    ///
+    /// This is a convenience for boxing the future into a [`LocalFutureObj`]
+    /// and calling [`LocalSpawn::spawn_local_obj`].
+    #[cfg(feature = "alloc")]
+    fn spawn_local<Fut>(&self, future: Fut) -> Result<(), SpawnError>
+    where
+        Fut: Future<Output = ()> + 'static,
+    {
+        self.spawn_local_obj(LocalFutureObj::new(Box::new(future)))
+    }
+
+    /// Spawns a task that polls the given future to completion and returns a
+    /// handle to its output.
+    ///
+    /// See [`SpawnExt::spawn_with_handle`].
+    #[cfg(feature = "std")]
+    fn spawn_local_with_handle<Fut>(
+        &self,
+        future: Fut,
+    ) -> Result<RemoteHandle<Fut::Output>, SpawnError>
+    where
+        Fut: Future + 'static,
+    {
+        let (future, handle) = future.remote_handle();
+        self.spawn_local(future)?;
+        Ok(handle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LocalSpawnExt, SpawnExt};
+    use crate::future::FutureExt;
+    use core::cell::{Cell, RefCell};
+    use core::task::{Context, Poll, Waker};
+    use futures_core::task::{FutureObj, LocalFutureObj, LocalSpawn, Spawn, SpawnError};
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    use std::rc::Rc;
+    use std::vec::Vec;
+
+    /// Queues spawned tasks, and polls them all once per `tick`.
+    #[derive(Default)]
+    struct Queue {
+        tasks: RefCell<Vec<LocalFutureObj<'static, ()>>>,
+        closed: Cell<bool>,
+    }
+
+    impl Queue {
+        fn tick(&self) {
+            let mut ctx = Context::from_waker(Waker::noop());
+            let tasks = self.tasks.take();
+            for mut task in tasks {
+                if task.poll_unpin(&mut ctx).is_pending() {
+                    self.tasks.borrow_mut().push(task);
+                }
+            }
+        }
+    }
+
+    impl Spawn for Queue {
+        fn spawn_obj(&self, future: FutureObj<'static, ()>) -> Result<(), SpawnError> {
+            self.spawn_local_obj(future.into())
+        }
+    }
+
+    impl LocalSpawn for Queue {
+        fn spawn_local_obj(&self, future: LocalFutureObj<'static, ()>) -> Result<(), SpawnError> {
+            if self.closed.get() {
+                return Err(SpawnError::shutdown());
+            }
+            self.tasks.borrow_mut().push(future);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn spawn_runs_future() {
+        let queue = Queue::default();
+        let hits = Rc::new(Cell::new(0));
+        let h = hits.clone();
+        queue
+            .spawn_local(async move { h.set(h.get() + 1) })
+            .unwrap();
+        queue.spawn(async {}).unwrap();
+        assert_eq!(queue.tasks.borrow().len(), 2);
+        queue.tick();
+        assert_eq!(hits.get(), 1);
+        assert!(queue.tasks.borrow().is_empty());
+    }
+
+    #[test]
+    fn spawn_reports_shutdown() {
+        let queue = Queue::default();
+        queue.closed.set(true);
+        let err = queue.spawn(async {}).unwrap_err();
+        assert!(err.is_shutdown());
+        assert!(queue.spawn_with_handle(async { 1 }).is_err());
+    }
+
+    #[test]
+    fn handle_resolves_to_output() {
+        let queue = Queue::default();
+        let mut handle = queue.spawn_with_handle(async { 7 }).unwrap();
+        let mut ctx = Context::from_waker(Waker::noop());
+        assert_eq!(handle.poll_unpin(&mut ctx), Poll::Pending);
+        queue.tick();
+        assert_eq!(handle.poll_unpin(&mut ctx), Poll::Ready(7));
+    }
+
+    #[test]
+    fn dropped_handle_cancels_and_forget_detaches() {
+        let queue = Queue::default();
+        let ran = Rc::new(Cell::new(0));
+
+        let r = ran.clone();
+        drop(queue.spawn_local_with_handle(async move { r.set(r.get() + 1) }));
+        queue.tick();
+        assert_eq!(ran.get(), 0);
+
+        let r = ran.clone();
+        queue
+            .spawn_local_with_handle(async move { r.set(r.get() + 1) })
+            .unwrap()
+            .forget();
+        queue.tick();
+        assert_eq!(ran.get(), 1);
+    }
+
+    #[test]
+    fn handle_resumes_panic() {
+        let queue = Queue::default();
+        let handle = queue
+            .spawn_with_handle(async { panic!("remote panic") })
+            .unwrap();
+        queue.tick();
+        let err = catch_unwind(AssertUnwindSafe(|| handle.now_or_never())).unwrap_err();
+        assert_eq!(err.downcast_ref::<&str>(), Some(&"remote panic"));
+    }
+}
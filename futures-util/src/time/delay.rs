@@ -0,0 +1,225 @@
+use super::timer::Timer;
+use super::wheel::Entry;
+use core::pin::Pin;
+use futures_core::future::{FusedFuture, Future};
+use futures_core::task::{Context, Poll};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// A future that completes once a deadline is reached.
+///
+/// The deadline is armed on creation, on the clock of the current thread
+/// (see [`MockClock`](super::MockClock)). Timers have millisecond
+/// resolution and never fire early.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Delay {
+    timer: Timer,
+    entry: Arc<Entry>,
+    deadline: Instant,
+    done: bool,
+}
+
+impl Delay {
+    /// Creates a delay that completes after `dur`.
+    pub fn new(dur: Duration) -> Self {
+        let timer = Timer::current();
+        let deadline = after(timer.now(), dur);
+        Self::with_timer(timer, deadline)
+    }
+
+    /// Creates a delay that completes at `deadline`.
+    pub fn until(deadline: Instant) -> Self {
+        Self::with_timer(Timer::current(), deadline)
+    }
+
+    fn with_timer(timer: Timer, deadline: Instant) -> Self {
+        let entry = Arc::new(Entry::default());
+        timer.register(&entry, deadline);
+        Self {
+            timer,
+            entry,
+            deadline,
+            done: false,
+        }
+    }
+
+    /// Returns the instant this delay completes at.
+    pub fn deadline(&self) -> Instant {
+        self.deadline
+    }
+
+    /// Re-arms this delay to complete at `deadline`, even if it already
+    /// completed.
+    pub fn reset(&mut self, deadline: Instant) {
+        self.deadline = deadline;
+        self.done = false;
+        self.timer.register(&self.entry, deadline);
+    }
+
+    /// Re-arms this delay to complete `dur` from now.
+    pub(crate) fn reset_after(&mut self, dur: Duration) {
+        let deadline = after(self.timer.now(), dur);
+        self.reset(deadline);
+    }
+
+    pub(super) fn timer(&self) -> &Timer {
+        &self.timer
+    }
+}
+
+/// `now + dur`, saturating at a deadline that is never reached.
+pub(super) fn after(now: Instant, dur: Duration) -> Instant {
+    now.checked_add(dur)
+        .unwrap_or_else(|| now + Duration::from_secs(86400 * 365 * 30))
+}
+
+impl Future for Delay {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<()> {
+        if self.done {
+            return Poll::Ready(());
+        }
+        let mut state = self.entry.state.lock().unwrap();
+        if state.fired {
+            drop(state);
+            self.done = true;
+            return Poll::Ready(());
+        }
+        if !state
+            .waker
+            .as_ref()
+            .is_some_and(|w| w.will_wake(ctx.waker()))
+        {
+            state.waker = Some(ctx.waker().clone());
+        }
+        Poll::Pending
+    }
+}
+
+impl FusedFuture for Delay {
+    fn is_terminated(&self) -> bool {
+        self.done
+    }
+}
+
+impl Drop for Delay {
+    fn drop(&mut self) {
+        self.timer.deregister(&self.entry);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Delay;
+    use crate::future::FutureExt;
+    use crate::time::MockClock;
+    use core::task::{Context, Poll, Waker};
+    use futures_core::future::{FusedFuture, Future};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::task::Wake;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    struct CountWakes(AtomicUsize);
+
+    impl Wake for CountWakes {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    struct Unpark(thread::Thread);
+
+    impl Wake for Unpark {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    fn block_on<F: Future + Unpin>(mut fut: F) -> F::Output {
+        let waker = Waker::from(Arc::new(Unpark(thread::current())));
+        let mut ctx = Context::from_waker(&waker);
+        loop {
+            match fut.poll_unpin(&mut ctx) {
+                Poll::Ready(out) => return out,
+                Poll::Pending => thread::park(),
+            }
+        }
+    }
+
+    #[test]
+    fn fires_when_the_mock_clock_passes_the_deadline() {
+        let clock = MockClock::new();
+        let _guard = clock.enter();
+        let counter = Arc::new(CountWakes(AtomicUsize::new(0)));
+        let waker = Waker::from(counter.clone());
+        let mut ctx = Context::from_waker(&waker);
+
+        let mut delay = Delay::new(Duration::from_millis(1500));
+        assert_eq!(delay.deadline(), clock.now() + Duration::from_millis(1500));
+        assert!(delay.poll_unpin(&mut ctx).is_pending());
+
+        clock.advance(Duration::from_millis(1499));
+        assert_eq!(counter.0.load(Ordering::SeqCst), 0);
+        assert!(delay.poll_unpin(&mut ctx).is_pending());
+
+        clock.advance(Duration::from_millis(1));
+        assert_eq!(counter.0.load(Ordering::SeqCst), 1);
+        assert!(!delay.is_terminated());
+        assert!(delay.poll_unpin(&mut ctx).is_ready());
+        assert!(delay.is_terminated());
+        assert!(delay.poll_unpin(&mut ctx).is_ready());
+    }
+
+    #[test]
+    fn reset_rearms() {
+        let clock = MockClock::new();
+        let _guard = clock.enter();
+
+        let mut delay = Delay::new(Duration::from_secs(1));
+        delay.reset(clock.now() + Duration::from_secs(5));
+        clock.advance(Duration::from_secs(1));
+        assert_eq!((&mut delay).now_or_never(), None);
+        clock.advance(Duration::from_secs(4));
+        assert_eq!((&mut delay).now_or_never(), Some(()));
+
+        delay.reset(clock.now() + Duration::from_secs(1));
+        assert!(!delay.is_terminated());
+        assert_eq!((&mut delay).now_or_never(), None);
+        clock.advance(Duration::from_secs(1));
+        assert_eq!((&mut delay).now_or_never(), Some(()));
+
+        // A deadline in the past completes right away.
+        delay.reset(clock.now() - Duration::from_secs(1));
+        assert_eq!(delay.now_or_never(), Some(()));
+    }
+
+    #[test]
+    fn dropped_and_reset_delays_leave_the_wheel() {
+        let clock = MockClock::new();
+        let _guard = clock.enter();
+
+        let mut delay = Delay::new(Duration::from_secs(3600));
+        for _ in 0..1000 {
+            drop(Delay::new(Duration::from_secs(3600)));
+            delay.reset_after(Duration::from_secs(3600));
+        }
+        assert_eq!(delay.timer().len(), 1);
+
+        let timer = delay.timer().clone();
+        drop(delay);
+        assert_eq!(timer.len(), 0);
+    }
+
+    #[test]
+    fn system_clock_delay() {
+        let start = Instant::now();
+        block_on(Delay::new(Duration::from_millis(20)));
+        assert!(start.elapsed() >= Duration::from_millis(20));
+        // Deadlines that don't fit in an `Instant` never fire.
+        assert_eq!(Delay::new(Duration::MAX).now_or_never(), None);
+    }
+}
@@ -0,0 +1,199 @@
+use super::delay::{after, Delay};
+use core::pin::Pin;
+use futures_core::future::Future;
+use futures_core::ready;
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+/// What an [`Interval`] does when it is polled so late that one or more
+/// ticks were missed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MissedTickBehavior {
+    /// Yields the missed ticks back to back until it caught up with the
+    /// original schedule.
+    #[default]
+    Burst,
+    /// Schedules the next tick one period after the late tick was yielded,
+    /// shifting the schedule.
+    Delay,
+    /// Drops the missed ticks and continues with the next tick of the
+    /// original schedule that is still in the future.
+    Skip,
+}
+
+impl MissedTickBehavior {
+    /// Returns the deadline after the tick scheduled at `tick`, which was
+    /// yielded at `now`.
+    fn next(self, tick: Instant, now: Instant, period: Duration) -> Instant {
+        let next = after(tick, period);
+        if next > now {
+            return next;
+        }
+        match self {
+            Self::Burst => next,
+            Self::Delay => after(now, period),
+            Self::Skip => {
+                let behind = now.duration_since(tick).as_nanos() % period.as_nanos();
+                after(now, period - Duration::from_nanos(behind as u64))
+            }
+        }
+    }
+}
+
+/// A stream that yields at a fixed period.
+///
+/// Each item is the instant the tick was scheduled for. Created by
+/// [`interval`] and [`interval_at`]; the stream never ends.
+#[derive(Debug)]
+#[must_use = "streams do nothing unless polled"]
+pub struct Interval {
+    delay: Delay,
+    period: Duration,
+    missed_tick_behavior: MissedTickBehavior,
+}
+
+/// Creates an interval whose first tick completes immediately.
+///
+/// # Panics
+///
+/// Panics if `period` is zero.
+pub fn interval(period: Duration) -> Interval {
+    interval_at(super::now(), period)
+}
+
+/// Creates an interval whose first tick completes at `start`.
+///
+/// # Panics
+///
+/// Panics if `period` is zero.
+pub fn interval_at(start: Instant, period: Duration) -> Interval {
+    assert!(period > Duration::ZERO, "`period` must be non-zero");
+    Interval {
+        delay: Delay::until(start),
+        period,
+        missed_tick_behavior: MissedTickBehavior::default(),
+    }
+}
+
+impl Interval {
+    /// Returns the period of this interval.
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+
+    /// Returns how this interval handles missed ticks.
+    pub fn missed_tick_behavior(&self) -> MissedTickBehavior {
+        self.missed_tick_behavior
+    }
+
+    /// Sets how this interval handles missed ticks.
+    pub fn set_missed_tick_behavior(&mut self, behavior: MissedTickBehavior) {
+        self.missed_tick_behavior = behavior;
+    }
+
+    /// Polls for the next tick.
+    pub fn poll_tick(&mut self, ctx: &mut Context<'_>) -> Poll<Instant> {
+        ready!(Pin::new(&mut self.delay).poll(ctx));
+        let tick = self.delay.deadline();
+        let now = self.delay.timer().now();
+        let next = self.missed_tick_behavior.next(tick, now, self.period);
+        self.delay.reset(next);
+        Poll::Ready(tick)
+    }
+}
+
+impl Stream for Interval {
+    type Item = Instant;
+
+    fn poll_next(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Option<Instant>> {
+        self.get_mut().poll_tick(ctx).map(Some)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+impl FusedStream for Interval {
+    fn is_terminated(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{interval, interval_at, Interval, MissedTickBehavior};
+    use crate::future::FutureExt;
+    use crate::stream::StreamExt;
+    use crate::time::MockClock;
+    use core::task::{Context, Poll, Waker};
+    use std::time::{Duration, Instant};
+    use std::vec;
+    use std::vec::Vec;
+
+    const MS: Duration = Duration::from_millis(1);
+
+    /// Drains the ticks that are ready now, as milliseconds since `start`.
+    fn ready_ticks(ticks: &mut Interval, start: Instant) -> Vec<u128> {
+        let mut ctx = Context::from_waker(Waker::noop());
+        let mut out = Vec::new();
+        while let Poll::Ready(Some(tick)) = ticks.poll_next_unpin(&mut ctx) {
+            out.push((tick - start).as_millis());
+        }
+        out
+    }
+
+    fn run(behavior: MissedTickBehavior) -> Vec<Vec<u128>> {
+        let clock = MockClock::new();
+        let start = clock.now();
+        let _guard = clock.enter();
+        let mut ticks = interval(10 * MS);
+        ticks.set_missed_tick_behavior(behavior);
+
+        let mut rounds = vec![ready_ticks(&mut ticks, start)];
+        clock.advance(10 * MS);
+        rounds.push(ready_ticks(&mut ticks, start));
+        // Fall behind by two and a half periods.
+        clock.advance(35 * MS);
+        rounds.push(ready_ticks(&mut ticks, start));
+        clock.advance(10 * MS);
+        rounds.push(ready_ticks(&mut ticks, start));
+        rounds
+    }
+
+    #[test]
+    fn burst_catches_up() {
+        assert_eq!(
+            run(MissedTickBehavior::Burst),
+            [vec![0], vec![10], vec![20, 30, 40], vec![50]]
+        );
+    }
+
+    #[test]
+    fn delay_shifts_the_schedule() {
+        assert_eq!(
+            run(MissedTickBehavior::Delay),
+            [vec![0], vec![10], vec![20], vec![55]]
+        );
+    }
+
+    #[test]
+    fn skip_keeps_the_schedule() {
+        assert_eq!(
+            run(MissedTickBehavior::Skip),
+            [vec![0], vec![10], vec![20], vec![50]]
+        );
+    }
+
+    #[test]
+    fn interval_at_waits_for_start() {
+        let clock = MockClock::new();
+        let _guard = clock.enter();
+        let mut ticks = interval_at(clock.now() + 5 * MS, 10 * MS);
+        assert_eq!(ticks.period(), 10 * MS);
+        assert_eq!(ticks.next().now_or_never(), None);
+        clock.advance(5 * MS);
+        assert_eq!(ticks.next().now_or_never(), Some(Some(clock.now())));
+    }
+}
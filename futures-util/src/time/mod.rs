@@ -0,0 +1,23 @@
+//! Timers.
+//!
+//! This module contains [`Delay`], a future that completes at a deadline,
+//! the [`Interval`] stream, and the [`timeout`] combinator; streams get
+//! [`StreamExt::timeout`](crate::StreamExt::timeout).
+//!
+//! All timers share a hierarchical timer wheel, driven by a background
+//! thread that is started on first use. Tests can run timers on a
+//! [`MockClock`] instead, which only moves when advanced.
+
+mod wheel;
+
+mod timer;
+pub use self::timer::{now, MockClock, MockGuard};
+
+mod delay;
+pub use self::delay::Delay;
+
+mod interval;
+pub use self::interval::{interval, interval_at, Interval, MissedTickBehavior};
+
+mod timeout;
+pub use self::timeout::{timeout, Elapsed, Timeout};
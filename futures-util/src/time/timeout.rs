@@ -0,0 +1,126 @@
+use super::delay::Delay;
+use core::fmt;
+use core::pin::Pin;
+use futures_core::future::{FusedFuture, Future};
+use futures_core::task::{Context, Poll};
+use pin_project_lite::pin_project;
+use std::time::Duration;
+
+/// The error returned when a timeout elapses first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Elapsed(());
+
+impl Elapsed {
+    pub(crate) fn new() -> Self {
+        Self(())
+    }
+}
+
+impl fmt::Display for Elapsed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("deadline has elapsed")
+    }
+}
+
+impl std::error::Error for Elapsed {}
+
+/// Requires `future` to complete within `dur`.
+///
+/// The returned future resolves to the output of `future`, or to
+/// [`Elapsed`] if `dur` passes first, in which case `future` is dropped
+/// with the timeout.
+///
+/// ```
+/// use futures_util::future::FutureExt;
+/// use futures_util::time::{timeout, MockClock};
+/// use std::future::pending;
+/// use std::time::Duration;
+///
+/// let clock = MockClock::new();
+/// let _guard = clock.enter();
+///
+/// let mut fut = timeout(Duration::from_secs(1), pending::<()>());
+/// assert_eq!((&mut fut).now_or_never(), None);
+/// clock.advance(Duration::from_secs(1));
+/// assert!(fut.now_or_never().unwrap().is_err());
+/// ```
+pub fn timeout<F: Future>(dur: Duration, future: F) -> Timeout<F> {
+    Timeout {
+        future,
+        delay: Delay::new(dur),
+    }
+}
+
+pin_project! {
+    /// Future for the [`timeout`] function.
+    #[derive(Debug)]
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct Timeout<F> {
+        #[pin]
+        future: F,
+        delay: Delay,
+    }
+}
+
+impl<F> Timeout<F> {
+    /// Returns a reference to the inner future.
+    pub fn get_ref(&self) -> &F {
+        &self.future
+    }
+
+    /// Consumes the timeout, returning the inner future.
+    pub fn into_inner(self) -> F {
+        self.future
+    }
+}
+
+impl<F: Future> Future for Timeout<F> {
+    type Output = Result<F::Output, Elapsed>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        // The future gets one last chance even if the deadline passed.
+        if let Poll::Ready(output) = this.future.poll(ctx) {
+            return Poll::Ready(Ok(output));
+        }
+        Pin::new(this.delay).poll(ctx).map(|()| Err(Elapsed::new()))
+    }
+}
+
+impl<F: FusedFuture> FusedFuture for Timeout<F> {
+    fn is_terminated(&self) -> bool {
+        self.future.is_terminated() || self.delay.is_terminated()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{timeout, Elapsed};
+    use crate::future::FutureExt;
+    use crate::time::MockClock;
+    use core::future::{pending, ready};
+    use std::string::ToString;
+    use std::time::Duration;
+
+    #[test]
+    fn completes_before_the_deadline() {
+        let clock = MockClock::new();
+        let _guard = clock.enter();
+        let fut = timeout(Duration::from_secs(1), ready(5));
+        clock.advance(Duration::from_secs(2));
+        // The future wins even when both are ready.
+        assert_eq!(fut.now_or_never(), Some(Ok(5)));
+    }
+
+    #[test]
+    fn elapses() {
+        let clock = MockClock::new();
+        let _guard = clock.enter();
+        let mut fut = timeout(Duration::from_millis(10), pending::<()>());
+        clock.advance(Duration::from_millis(9));
+        assert_eq!((&mut fut).now_or_never(), None);
+        clock.advance(Duration::from_millis(1));
+        assert_eq!(fut.now_or_never(), Some(Err(Elapsed::new())));
+        assert_eq!(Elapsed::new().to_string(), "deadline has elapsed");
+    }
+}
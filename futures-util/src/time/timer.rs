@@ -0,0 +1,267 @@
+use super::wheel::{Entry, Wheel};
+use core::fmt;
+use core::marker::PhantomData;
+use std::cell::RefCell;
+use std::sync::{Arc, Condvar, Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// A handle to a timer wheel and the clock driving it.
+#[derive(Clone)]
+pub(super) struct Timer {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    /// The instant of tick zero.
+    start: Instant,
+    clock: Clock,
+    wheel: Mutex<Wheel>,
+}
+
+enum Clock {
+    /// Follows `Instant::now`. The condvar wakes the driver thread when a
+    /// timer expiring before its next wakeup is added.
+    System(Condvar),
+    /// Only moves when advanced, by the stored offset from `start`.
+    Mock(Mutex<Duration>),
+}
+
+std::thread_local! {
+    static CURRENT: RefCell<Option<Timer>> = const { RefCell::new(None) };
+}
+
+impl Timer {
+    /// Returns the timer entered on this thread with [`MockClock::enter`],
+    /// or else the global system timer.
+    pub(super) fn current() -> Self {
+        CURRENT
+            .with(|current| current.borrow().clone())
+            .unwrap_or_else(Self::system)
+    }
+
+    fn system() -> Self {
+        static SYSTEM: OnceLock<Timer> = OnceLock::new();
+        SYSTEM
+            .get_or_init(|| {
+                let timer = Self::new(Clock::System(Condvar::new()));
+                let driver = timer.clone();
+                thread::Builder::new()
+                    .name("futures-timer".into())
+                    .spawn(move || driver.run())
+                    .expect("failed to spawn the timer thread");
+                timer
+            })
+            .clone()
+    }
+
+    fn new(clock: Clock) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                start: Instant::now(),
+                clock,
+                wheel: Mutex::new(Wheel::new()),
+            }),
+        }
+    }
+
+    pub(super) fn now(&self) -> Instant {
+        match &self.inner.clock {
+            Clock::System(_) => Instant::now(),
+            Clock::Mock(offset) => self.inner.start + *offset.lock().unwrap(),
+        }
+    }
+
+    fn now_tick(&self) -> u64 {
+        self.now()
+            .saturating_duration_since(self.inner.start)
+            .as_millis() as u64
+    }
+
+    /// The first tick at or after `deadline`, so that timers never fire
+    /// early.
+    fn tick_at(&self, deadline: Instant) -> u64 {
+        let since = deadline.saturating_duration_since(self.inner.start);
+        let ms = since.as_millis() as u64;
+        if since > Duration::from_millis(ms) {
+            ms + 1
+        } else {
+            ms
+        }
+    }
+
+    /// (Re)arms `entry` to fire at `deadline`.
+    pub(super) fn register(&self, entry: &Arc<Entry>, deadline: Instant) {
+        let when = self.tick_at(deadline);
+        let waker = {
+            let mut wheel = self.inner.wheel.lock().unwrap();
+            let mut state = entry.state.lock().unwrap();
+            wheel.remove(&mut state);
+            state.when = when;
+            state.fired = false;
+            if when <= self.now_tick() {
+                state.fire()
+            } else {
+                let earlier = wheel.next_deadline().is_none_or(|next| when < next);
+                let waker = wheel.insert(entry, &mut state, when);
+                if let (Clock::System(driver), true) = (&self.inner.clock, earlier) {
+                    driver.notify_one();
+                }
+                waker
+            }
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+
+    /// Takes `entry` out of the wheel, if it is in it.
+    pub(super) fn deregister(&self, entry: &Entry) {
+        // Besides `register`, only the wheel puts an entry in, when moving
+        // one that is already in it, so one that isn't in it now stays out.
+        if !entry.state.lock().unwrap().in_wheel() {
+            return;
+        }
+        let mut wheel = self.inner.wheel.lock().unwrap();
+        wheel.remove(&mut entry.state.lock().unwrap());
+    }
+
+    /// Returns the number of timers in the wheel.
+    #[cfg(test)]
+    pub(super) fn len(&self) -> usize {
+        self.inner.wheel.lock().unwrap().len()
+    }
+
+    /// Fires every timer that expired by now.
+    fn fire_expired(&self) {
+        let wakers = self.inner.wheel.lock().unwrap().advance(self.now_tick());
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+
+    /// The loop of the system timer's driver thread.
+    fn run(&self) {
+        let Clock::System(driver) = &self.inner.clock else {
+            unreachable!()
+        };
+        let mut wheel = self.inner.wheel.lock().unwrap();
+        loop {
+            let wakers = wheel.advance(self.now_tick());
+            if !wakers.is_empty() {
+                drop(wheel);
+                for waker in wakers {
+                    waker.wake();
+                }
+                wheel = self.inner.wheel.lock().unwrap();
+                continue;
+            }
+            wheel = match wheel.next_deadline() {
+                Some(tick) => {
+                    let at = self.inner.start + Duration::from_millis(tick);
+                    let timeout = at.saturating_duration_since(Instant::now());
+                    driver.wait_timeout(wheel, timeout).unwrap().0
+                }
+                None => driver.wait(wheel).unwrap(),
+            };
+        }
+    }
+}
+
+impl fmt::Debug for Timer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let clock = match self.inner.clock {
+            Clock::System(_) => "system",
+            Clock::Mock(_) => "mock",
+        };
+        f.debug_struct("Timer").field("clock", &clock).finish()
+    }
+}
+
+/// A clock that only moves when told to, for testing code that uses timers.
+///
+/// Timers created on a thread while a clock is [entered](MockClock::enter)
+/// run on that clock, and fire as soon as [`advance`](MockClock::advance)
+/// moves it past their deadline. Clones share the same time.
+///
+/// ```
+/// use futures_util::future::FutureExt;
+/// use futures_util::time::{Delay, MockClock};
+/// use std::time::Duration;
+///
+/// let clock = MockClock::new();
+/// let _guard = clock.enter();
+///
+/// let mut delay = Delay::new(Duration::from_secs(60));
+/// assert_eq!((&mut delay).now_or_never(), None);
+/// clock.advance(Duration::from_secs(60));
+/// assert_eq!(delay.now_or_never(), Some(()));
+/// ```
+#[derive(Debug, Clone)]
+pub struct MockClock {
+    timer: Timer,
+}
+
+impl MockClock {
+    /// Creates a clock that starts at the current instant.
+    pub fn new() -> Self {
+        Self {
+            timer: Timer::new(Clock::Mock(Mutex::new(Duration::ZERO))),
+        }
+    }
+
+    /// Returns the clock's current time.
+    pub fn now(&self) -> Instant {
+        self.timer.now()
+    }
+
+    /// Moves the clock forward by `dur`, waking every timer that expires on
+    /// the way.
+    pub fn advance(&self, dur: Duration) {
+        let Clock::Mock(offset) = &self.timer.inner.clock else {
+            unreachable!()
+        };
+        *offset.lock().unwrap() += dur;
+        self.timer.fire_expired();
+    }
+
+    /// Makes timers created on this thread use this clock, until the
+    /// returned guard is dropped.
+    pub fn enter(&self) -> MockGuard {
+        let prev = CURRENT.with(|current| current.replace(Some(self.timer.clone())));
+        MockGuard {
+            prev,
+            _not_send: PhantomData,
+        }
+    }
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Guard returned by [`MockClock::enter`], restoring the previous clock when
+/// dropped.
+#[must_use = "the clock is only entered until the guard is dropped"]
+#[derive(Debug)]
+pub struct MockGuard {
+    prev: Option<Timer>,
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Drop for MockGuard {
+    fn drop(&mut self) {
+        CURRENT.with(|current| *current.borrow_mut() = self.prev.take());
+    }
+}
+
+/// Returns the current time of the clock timers on this thread use.
+///
+/// This is [`Instant::now`] unless a [`MockClock`] is entered.
+pub fn now() -> Instant {
+    CURRENT
+        .with(|current| current.borrow().as_ref().map(Timer::now))
+        .unwrap_or_else(Instant::now)
+}
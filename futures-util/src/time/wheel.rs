@@ -0,0 +1,298 @@
+//! A hierarchical timer wheel.
+//!
+//! Time is counted in ticks of one millisecond. The wheel has six levels of
+//! 64 slots each; a slot on level `n` covers `64^n` ticks, so the levels
+//! together cover about two years ahead of the current tick. A timer is
+//! stored on the lowest level whose slot tells it apart from the current
+//! tick, and moves down a level each time its slot is reached until it
+//! expires. Each entry records where it is stored, so that timers which are
+//! reset or dropped are taken out of the wheel right away.
+
+use std::array;
+use std::sync::{Arc, Mutex};
+use std::task::Waker;
+use std::vec::Vec;
+
+const LEVEL_BITS: u32 = 6;
+const SLOTS: usize = 1 << LEVEL_BITS;
+const NUM_LEVELS: usize = 6;
+
+/// Timers further ahead than this are stored at the furthest slot and
+/// re-inserted when it is reached.
+const MAX_TICKS: u64 = 1 << (LEVEL_BITS * NUM_LEVELS as u32);
+
+/// The shared state of one timer.
+#[derive(Debug, Default)]
+pub(super) struct Entry {
+    pub(super) state: Mutex<EntryState>,
+}
+
+#[derive(Debug, Default)]
+pub(super) struct EntryState {
+    /// The tick the timer expires at.
+    pub(super) when: u64,
+    pub(super) fired: bool,
+    pub(super) waker: Option<Waker>,
+    /// The level, slot and index in the slot the entry is stored at, while
+    /// it is in the wheel. Only changed with the wheel locked.
+    position: Option<(usize, usize, usize)>,
+}
+
+impl EntryState {
+    pub(super) fn in_wheel(&self) -> bool {
+        self.position.is_some()
+    }
+
+    pub(super) fn fire(&mut self) -> Option<Waker> {
+        self.fired = true;
+        self.waker.take()
+    }
+}
+
+type Slot = Vec<Arc<Entry>>;
+
+#[derive(Debug)]
+struct Level {
+    /// Bit `i` is set if slot `i` is non-empty.
+    occupied: u64,
+    slots: [Slot; SLOTS],
+}
+
+impl Level {
+    fn slot_ticks(level: usize) -> u64 {
+        1 << (LEVEL_BITS * level as u32)
+    }
+
+    /// Returns the index and start tick of the first occupied slot at or
+    /// after `elapsed`.
+    fn next_occupied(&self, level: usize, elapsed: u64) -> Option<(usize, u64)> {
+        if self.occupied == 0 {
+            return None;
+        }
+        let slot_ticks = Self::slot_ticks(level);
+        let level_ticks = slot_ticks << LEVEL_BITS;
+        let now_slot = (elapsed / slot_ticks) as usize % SLOTS;
+
+        let zeros = self.occupied.rotate_right(now_slot as u32).trailing_zeros() as usize;
+        let slot = (zeros + now_slot) % SLOTS;
+
+        let mut deadline = (elapsed & !(level_ticks - 1)) + slot as u64 * slot_ticks;
+        if deadline < elapsed {
+            deadline += level_ticks;
+        }
+        Some((slot, deadline))
+    }
+}
+
+#[derive(Debug)]
+pub(super) struct Wheel {
+    /// The tick up to which all timers have been processed.
+    elapsed: u64,
+    levels: [Level; NUM_LEVELS],
+}
+
+impl Wheel {
+    pub(super) fn new() -> Self {
+        Self {
+            elapsed: 0,
+            levels: array::from_fn(|_| Level {
+                occupied: 0,
+                slots: array::from_fn(|_| Vec::new()),
+            }),
+        }
+    }
+
+    /// Inserts an entry that expires at tick `when`, returning its waker if
+    /// that tick has already been processed.
+    ///
+    /// `state` is the locked state of `entry`, which must not be in the
+    /// wheel already.
+    pub(super) fn insert(
+        &mut self,
+        entry: &Arc<Entry>,
+        state: &mut EntryState,
+        when: u64,
+    ) -> Option<Waker> {
+        debug_assert!(state.position.is_none());
+        if when <= self.elapsed {
+            return state.fire();
+        }
+        let at = when.min(self.elapsed + MAX_TICKS - 1);
+        // The highest bit in which the expiration differs from the current
+        // tick picks the level.
+        let masked = ((self.elapsed ^ at) | (SLOTS as u64 - 1)).min(MAX_TICKS - 1);
+        let significant = 63 - masked.leading_zeros();
+        let level = (significant / LEVEL_BITS) as usize;
+        let slot = ((at >> (LEVEL_BITS * level as u32)) as usize) % SLOTS;
+
+        let slots = &mut self.levels[level].slots[slot];
+        state.position = Some((level, slot, slots.len()));
+        slots.push(entry.clone());
+        self.levels[level].occupied |= 1 << slot;
+        None
+    }
+
+    /// Takes the entry whose locked state is `state` out of the wheel, if it
+    /// is in it.
+    pub(super) fn remove(&mut self, state: &mut EntryState) {
+        let Some(position @ (level, slot, index)) = state.position.take() else {
+            return;
+        };
+        let slots = &mut self.levels[level].slots[slot];
+        slots.swap_remove(index);
+        if let Some(moved) = slots.get(index) {
+            // The last entry of the slot took the removed one's place.
+            moved.state.lock().unwrap().position = Some(position);
+        } else if slots.is_empty() {
+            self.levels[level].occupied &= !(1 << slot);
+        }
+    }
+
+    /// Returns the number of entries in the wheel.
+    #[cfg(test)]
+    pub(super) fn len(&self) -> usize {
+        self.levels
+            .iter()
+            .flat_map(|level| &level.slots)
+            .map(Vec::len)
+            .sum()
+    }
+
+    /// Returns the level, slot and start tick of the next slot to process.
+    fn next_expiration(&self) -> Option<(usize, usize, u64)> {
+        // Every slot on a level starts after all slots on the levels below
+        // it, so the lowest occupied level holds the next expiration.
+        self.levels.iter().enumerate().find_map(|(i, level)| {
+            level
+                .next_occupied(i, self.elapsed)
+                .map(|(slot, deadline)| (i, slot, deadline))
+        })
+    }
+
+    /// Returns the tick at which the next slot has to be processed.
+    pub(super) fn next_deadline(&self) -> Option<u64> {
+        self.next_expiration().map(|(_, _, deadline)| deadline)
+    }
+
+    /// Processes all timers up to tick `now`, returning the wakers of those
+    /// that expired.
+    pub(super) fn advance(&mut self, now: u64) -> Vec<Waker> {
+        let mut wakers = Vec::new();
+        while let Some((level, slot, deadline)) = self.next_expiration() {
+            if deadline > now {
+                break;
+            }
+            let level = &mut self.levels[level];
+            level.occupied &= !(1 << slot);
+            let entries = std::mem::take(&mut level.slots[slot]);
+
+            self.elapsed = deadline;
+            for entry in entries {
+                let mut state = entry.state.lock().unwrap();
+                state.position = None;
+                let when = state.when;
+                if when <= self.elapsed {
+                    wakers.extend(state.fire());
+                } else {
+                    wakers.extend(self.insert(&entry, &mut state, when));
+                }
+            }
+        }
+        self.elapsed = self.elapsed.max(now);
+        wakers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Entry, Wheel, MAX_TICKS};
+    use std::sync::Arc;
+    use std::task::Waker;
+    use std::vec::Vec;
+
+    /// (Re)arms `entry` at `when`, the way the timer does.
+    fn arm(wheel: &mut Wheel, entry: &Arc<Entry>, when: u64) -> Option<Waker> {
+        let mut state = entry.state.lock().unwrap();
+        wheel.remove(&mut state);
+        state.when = when;
+        state.fired = false;
+        wheel.insert(entry, &mut state, when)
+    }
+
+    fn fired(entries: &[Arc<Entry>]) -> Vec<bool> {
+        entries
+            .iter()
+            .map(|e| e.state.lock().unwrap().fired)
+            .collect()
+    }
+
+    #[test]
+    fn fires_in_order_across_levels() {
+        let mut wheel = Wheel::new();
+        let whens = [1, 63, 64, 200, 4096 + 5, 300_000, MAX_TICKS * 2];
+        let entries: Vec<_> = whens.iter().map(|_| Arc::default()).collect();
+        for (e, &w) in entries.iter().zip(&whens) {
+            assert!(arm(&mut wheel, e, w).is_none());
+        }
+        assert_eq!(wheel.len(), whens.len());
+
+        for (i, &w) in whens.iter().enumerate() {
+            assert_eq!(wheel.next_deadline().map(|d| d <= w), Some(true));
+            wheel.advance(w - 1);
+            assert!(!fired(&entries)[i]);
+            wheel.advance(w);
+            let expected: Vec<_> = (0..whens.len()).map(|j| j <= i).collect();
+            assert_eq!(fired(&entries), expected);
+        }
+        assert_eq!(wheel.next_deadline(), None);
+        assert_eq!(wheel.len(), 0);
+    }
+
+    #[test]
+    fn reset_and_removed_entries_leave_the_wheel() {
+        let mut wheel = Wheel::new();
+        let reset = Arc::default();
+        let removed: Arc<Entry> = Arc::default();
+        let kept = Arc::default();
+        arm(&mut wheel, &reset, 10);
+        arm(&mut wheel, &removed, 10);
+        arm(&mut wheel, &kept, 10);
+
+        arm(&mut wheel, &reset, 500);
+        wheel.remove(&mut removed.state.lock().unwrap());
+        assert_eq!(wheel.len(), 2);
+
+        wheel.advance(100);
+        assert_eq!(fired(&[reset.clone(), removed, kept]), [false, false, true]);
+        wheel.advance(500);
+        assert!(reset.state.lock().unwrap().fired);
+        assert_eq!(wheel.next_deadline(), None);
+
+        // Anything at or before the processed tick fires right away.
+        let late = Arc::default();
+        assert!(arm(&mut wheel, &late, 400).is_none());
+        assert!(late.state.lock().unwrap().fired);
+        assert_eq!(wheel.len(), 0);
+    }
+
+    #[test]
+    fn stays_bounded_under_churn() {
+        let mut wheel = Wheel::new();
+        let entries: Vec<Arc<Entry>> = (0..100).map(|_| Arc::default()).collect();
+        for round in 0..1000 {
+            for (i, entry) in entries.iter().enumerate() {
+                arm(&mut wheel, entry, 3_600_000 + round + i as u64);
+            }
+        }
+        assert_eq!(wheel.len(), entries.len());
+
+        for entry in &entries[..50] {
+            wheel.remove(&mut entry.state.lock().unwrap());
+        }
+        assert_eq!(wheel.len(), 50);
+        wheel.advance(3_600_000 + 2000);
+        assert_eq!(fired(&entries).iter().filter(|&&f| f).count(), 50);
+        assert_eq!(wheel.len(), 0);
+        assert_eq!(wheel.next_deadline(), None);
+    }
+}
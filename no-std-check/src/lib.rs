@@ -0,0 +1,78 @@
+//! Compile-only check that the core crates work for a `no_std` consumer.
+
+#![no_std]
+
+use core::pin::Pin;
+use futures_core::task::__internal::AtomicWaker;
+use futures_core::task::{Context, Poll};
+use futures_core::{ready, FusedFuture, Future, Sink, Stream, TryStream};
+
+pub struct Signal {
+    waker: AtomicWaker,
+    ready: bool,
+}
+
+impl Future for Signal {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<()> {
+        if self.ready {
+            Poll::Ready(())
+        } else {
+            self.waker.register(ctx.waker());
+            Poll::Pending
+        }
+    }
+}
+
+impl FusedFuture for Signal {
+    fn is_terminated(&self) -> bool {
+        self.ready
+    }
+}
+
+pub fn poll_twice<F: Future + Unpin>(fut: &mut F, ctx: &mut Context<'_>) -> Poll<F::Output> {
+    ready!(Pin::new(&mut *fut).poll(ctx));
+    Poll::Pending
+}
+
+pub fn poll_item<S: Stream + Unpin>(
+    stream: &mut S,
+    ctx: &mut Context<'_>,
+) -> Poll<Option<S::Item>> {
+    Pin::new(stream).poll_next(ctx)
+}
+
+pub fn try_poll_item<S: TryStream + Unpin>(
+    stream: &mut S,
+    ctx: &mut Context<'_>,
+) -> Poll<Option<Result<S::Ok, S::Error>>> {
+    Pin::new(stream).try_poll_next(ctx)
+}
+
+pub fn ready_to_send<T, S: Sink<T> + Unpin>(sink: &mut S, ctx: &mut Context<'_>) -> bool {
+    matches!(Pin::new(sink).poll_ready(ctx), Poll::Ready(Ok(())))
+}
+
+#[cfg(feature = "alloc")]
+pub mod with_alloc {
+    extern crate alloc;
+
+    use alloc::boxed::Box;
+    use futures_channel::oneshot;
+    use futures_core::future::BoxFuture;
+    use futures_core::stream::BoxStream;
+
+    pub fn boxed_receiver(
+        rx: oneshot::Receiver<u32>,
+    ) -> BoxFuture<'static, Result<u32, oneshot::Canceled>> {
+        Box::pin(rx)
+    }
+
+    pub fn boxed_stream<S>(stream: S) -> BoxStream<'static, S::Item>
+    where
+        S: futures_core::Stream + Send + 'static,
+    {
+        Box::pin(stream)
+    }
+}
@@ -1,3 +1,3 @@
-mod prelude {
+pub mod prelude {
     pub use std::future::Future;
 }
@@ -1,4 +1,4 @@
-use std::collections::{BTreeMap, HashMap};
+use std::collections::BTreeMap;
 use std::io;
 use std::io::prelude::*;
 
@@ -55,6 +55,6 @@ fn state_get_data() {
     }
 
     println!("{:?}", counts);
-    let sum: i32 = counts.values().into_iter().sum();
+    let sum: i32 = counts.values().sum();
     println!("sum: {}", sum);
 }
@@ -0,0 +1,31 @@
+use std::path::Path;
+use std::process::Command;
+
+// Builds `no-std-check` on its own, so features requested by the other
+// workspace members can't leak `std` into the core crates.
+fn check_no_std(features: &[&str]) {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let status = Command::new(env!("CARGO"))
+        .arg("check")
+        .arg("--offline")
+        .arg("--manifest-path")
+        .arg(root.join("no-std-check/Cargo.toml"))
+        .args(features.iter().flat_map(|f| ["--features", f]))
+        .env("CARGO_TARGET_DIR", root.join("target/no-std-check"))
+        .status()
+        .unwrap();
+    assert!(
+        status.success(),
+        "no_std check failed with features {features:?}"
+    );
+}
+
+#[test]
+fn core_crates_build_without_std() {
+    check_no_std(&[]);
+}
+
+#[test]
+fn core_crates_build_with_alloc_only() {
+    check_no_std(&["alloc"]);
+}